@@ -1,17 +1,33 @@
-use advent_of_code::solve;
+use advent_of_code::{available, parse, Answers};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use std::fs::read_to_string;
 
 pub fn criterion_benchmark(c: &mut Criterion) {
-    let end_day = 25;
-    for day in 1..=end_day {
-        let input_path = format!("src/year2021/day{:02}_input.txt", day);
+    let answers: Answers = read_to_string("answers.toml").unwrap().parse().unwrap();
+
+    for (year, day) in available() {
+        let input_path = format!("src/year{}/day{:02}_input.txt", year, day);
         let input = read_to_string(input_path).unwrap();
 
+        let benchmark_name = format!("{}_{:02}_parse", year, day);
+        c.bench_function(&benchmark_name, |b| {
+            b.iter(|| parse(black_box(year), black_box(day), black_box(&input)));
+        });
+
+        // days whose input cannot be parsed have nothing left to solve
+        let parsed = match parse(year, day, &input) {
+            Ok(parsed) => parsed,
+            Err(_) => continue,
+        };
         for part in 1..=2 {
-            let benchmark_name = format!("2021_{:02}_{}", day, part);
+            // benchmarking a wrong answer is pointless
+            if let Some(expected) = answers.get(year, day, part) {
+                assert_eq!(parsed.solve(part).as_deref(), Ok(expected));
+            }
+
+            let benchmark_name = format!("{}_{:02}_{}", year, day, part);
             c.bench_function(&benchmark_name, |b| {
-                b.iter(|| solve(black_box(day), black_box(part), black_box(&input)));
+                b.iter(|| parsed.solve(black_box(part)));
             });
         }
     }
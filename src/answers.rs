@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::error::AocError;
+
+/// Answers holds the expected answer of each part of the puzzles, read from a
+/// TOML file like:
+///
+/// ```toml
+/// [2021.day01]
+/// part1 = "1681"
+/// part2 = "1704"
+/// ```
+///
+/// Only this subset of TOML is supported: one table per year and day, holding
+/// the answers of its parts as strings. An answer of `"?"` marks a part whose
+/// answer is not known yet.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: HashMap<(u16, u8, u8), String>,
+}
+
+impl Answers {
+    /// Returns the expected answers to the inputs of this repository.
+    #[cfg(test)]
+    pub fn expected() -> Self {
+        include_str!("../answers.toml")
+            .parse()
+            .expect("answers.toml is not valid")
+    }
+
+    const UNKNOWN: &'static str = "?";
+
+    /// Returns the expected answer of the given part, if known.
+    pub fn get(&self, year: u16, day: u8, part: u8) -> Option<&str> {
+        self.answers
+            .get(&(year, day, part))
+            .map(String::as_str)
+            .filter(|&answer| answer != Self::UNKNOWN)
+    }
+
+    /// Tells whether the given part is listed, even as unknown.
+    pub fn contains(&self, year: u16, day: u8, part: u8) -> bool {
+        self.answers.contains_key(&(year, day, part))
+    }
+}
+
+impl FromStr for Answers {
+    type Err = AocError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut answers = HashMap::new();
+        let mut table = None;
+
+        for (i, line) in text.lines().enumerate() {
+            let column = line.len() - line.trim_start().len() + 1;
+            let line = line.trim();
+            let error = |message: &str| AocError::parse(i + 1, column, line, message);
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                table = Some(parse_table(header).ok_or_else(|| error("Invalid table"))?);
+                continue;
+            }
+
+            let (year, day) = table.ok_or_else(|| error("Answer outside of a table"))?;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("Invalid answer"))?;
+            let part = key
+                .trim()
+                .strip_prefix("part")
+                .and_then(|part| part.parse::<u8>().ok())
+                .filter(|part| (1..=2).contains(part))
+                .ok_or_else(|| error("Invalid part"))?;
+            let answer = value
+                .trim()
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .filter(|answer| !answer.contains(['"', '\\']))
+                .ok_or_else(|| error("Answer is not a plain string"))?;
+
+            if answers
+                .insert((year, day, part), answer.to_string())
+                .is_some()
+            {
+                return Err(error("Duplicate answer"));
+            }
+        }
+
+        Ok(Self { answers })
+    }
+}
+
+/// Parses a `year.dayNN` table name.
+fn parse_table(header: &str) -> Option<(u16, u8)> {
+    let (year, day) = header.trim().split_once('.')?;
+    let year = year.parse::<u16>().ok()?;
+    let day = day.strip_prefix("day")?.parse::<u8>().ok()?;
+    Some((year, day))
+}
+
+#[test]
+pub fn test_parse() -> Result<(), AocError> {
+    let answers = r#"
+    # answers to the puzzles of 2021
+    [2021.day01]
+    part1 = "1681"
+    part2 = "1704"
+
+    [2021.day13]
+    part2 = "HGAJBEHC"
+
+    [2021.day14]
+    part1 = "?"
+    "#
+    .parse::<Answers>()?;
+
+    assert_eq!(answers.get(2021, 1, 1), Some("1681"));
+    assert_eq!(answers.get(2021, 1, 2), Some("1704"));
+    assert_eq!(answers.get(2021, 13, 1), None);
+    assert_eq!(answers.get(2021, 13, 2), Some("HGAJBEHC"));
+    assert_eq!(answers.get(2021, 14, 1), None);
+    assert!(answers.contains(2021, 14, 1));
+    assert!(!answers.contains(2021, 13, 1));
+
+    assert_eq!(
+        "part1 = \"1\"".parse::<Answers>(),
+        Err(AocError::parse(
+            1,
+            1,
+            "part1 = \"1\"",
+            "Answer outside of a table"
+        ))
+    );
+    assert_eq!(
+        "[2021.day01]\n  part3 = \"1\"".parse::<Answers>(),
+        Err(AocError::parse(2, 3, "part3 = \"1\"", "Invalid part"))
+    );
+    assert_eq!(
+        "[2021.day01]\npart1 = 1".parse::<Answers>(),
+        Err(AocError::parse(
+            2,
+            1,
+            "part1 = 1",
+            "Answer is not a plain string"
+        ))
+    );
+    assert_eq!(
+        "[2021.day01]\npart1 = \"1\"\npart1 = \"2\"".parse::<Answers>(),
+        Err(AocError::parse(3, 1, "part1 = \"2\"", "Duplicate answer"))
+    );
+    assert_eq!(
+        "[2021.first]".parse::<Answers>(),
+        Err(AocError::parse(1, 1, "[2021.first]", "Invalid table"))
+    );
+
+    Ok(())
+}
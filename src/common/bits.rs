@@ -0,0 +1,151 @@
+#![allow(dead_code)]
+
+use std::ops::Range;
+use std::rc::Rc;
+
+use crate::common::parse_lines::{lines, Span};
+use crate::error::AocError;
+
+/// BitReader reads fields of arbitrary width from a stream of bits, most
+/// significant bit first.
+#[derive(Debug, Clone)]
+pub struct BitReader {
+    /// The whole stream, shared with the sub-readers
+    bits: Rc<[bool]>,
+    /// The index of the next bit to be read
+    position: usize,
+    /// The index after the last bit this reader may read
+    end: usize,
+}
+
+impl BitReader {
+    /// Builds a reader over the hex digits of the text, ignoring whitespace
+    /// and line breaks.
+    pub fn from_hex(text: &str) -> Result<Self, AocError> {
+        Self::from_spans(lines(text), 16)
+    }
+
+    /// Builds a reader over the binary digits of the text, ignoring
+    /// whitespace and line breaks.
+    pub fn from_binary(text: &str) -> Result<Self, AocError> {
+        Self::from_spans(lines(text), 2)
+    }
+
+    /// Builds a reader over the digits of the spans in the radix, which must
+    /// be a power of two from 2 to 16. Each digit gives as many bits as
+    /// needed to write the largest one, for instance 4 in hex.
+    pub fn from_spans<'a, I>(spans: I, radix: u32) -> Result<Self, AocError>
+    where
+        I: IntoIterator<Item = Span<'a>>,
+    {
+        assert!(
+            radix.is_power_of_two() && (2..=16).contains(&radix),
+            "Invalid radix {}",
+            radix
+        );
+        let width = radix.trailing_zeros();
+
+        let mut bits = Vec::new();
+        for span in spans {
+            for (i, c) in span.text.chars().enumerate() {
+                if c.is_whitespace() {
+                    continue;
+                }
+                let digit = c.to_digit(radix).ok_or_else(|| {
+                    let message = format!("Invalid base {} digit", radix);
+                    AocError::parse(span.line, span.column + i, &c.to_string(), &message)
+                })?;
+                bits.extend((0..width).rev().map(|bit| digit >> bit & 1 == 1));
+            }
+        }
+
+        Ok(Self {
+            end: bits.len(),
+            bits: bits.into(),
+            position: 0,
+        })
+    }
+
+    /// Returns the index of the next bit in the whole stream, so that
+    /// sub-readers share the positions of their parent.
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the number of bits left to read.
+    pub const fn remaining(&self) -> usize {
+        self.end - self.position
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.position == self.end
+    }
+
+    /// Reads the next `bits` bits as a number. Panics above 64 bits.
+    pub fn read(&mut self, bits: usize) -> Result<u64, AocError> {
+        assert!(bits <= 64, "Cannot read {} bits into a u64", bits);
+        let field = self.advance(bits)?;
+        Ok(self.bits[field]
+            .iter()
+            .fold(0, |value, &bit| value << 1 | u64::from(bit)))
+    }
+
+    /// Reads the next bit as a flag.
+    pub fn read_flag(&mut self) -> Result<bool, AocError> {
+        Ok(self.read(1)? == 1)
+    }
+
+    /// Returns a reader over the next `bits` bits, which this reader skips.
+    pub fn take(&mut self, bits: usize) -> Result<Self, AocError> {
+        let field = self.advance(bits)?;
+        Ok(Self {
+            bits: Rc::clone(&self.bits),
+            position: field.start,
+            end: field.end,
+        })
+    }
+
+    /// Moves past the next `bits` bits, returning their range.
+    fn advance(&mut self, bits: usize) -> Result<Range<usize>, AocError> {
+        if bits > self.remaining() {
+            return Err(AocError::InvalidInput(format!(
+                "Unexpected end of stream reading {} bits at position {}",
+                bits, self.position
+            )));
+        }
+        let start = self.position;
+        self.position += bits;
+        Ok(start..self.position)
+    }
+}
+
+#[test]
+pub fn test_read() -> Result<(), AocError> {
+    let mut reader = BitReader::from_hex("D2FE28")?;
+    assert_eq!(reader.remaining(), 24);
+    assert_eq!(reader.read(3)?, 6);
+    assert_eq!(reader.read(3)?, 4);
+    assert!(reader.read_flag()?);
+    assert_eq!(reader.position(), 7);
+
+    let mut group = reader.take(4)?;
+    assert_eq!(reader.position(), 11);
+    assert_eq!((group.position(), group.read(4)?), (7, 0b0111));
+    assert!(group.is_empty());
+    assert_eq!(
+        group.read(1),
+        Err(AocError::InvalidInput(
+            "Unexpected end of stream reading 1 bits at position 11".to_string()
+        ))
+    );
+
+    let mut reader = BitReader::from_binary("1111\n  0000 1")?;
+    assert_eq!(reader.read(9)?, 0b1_1110_0001);
+    assert_eq!(reader.read(0)?, 0);
+    assert_eq!(
+        BitReader::from_binary("10\n 12").err(),
+        Some(AocError::parse(2, 3, "2", "Invalid base 2 digit"))
+    );
+
+    Ok(())
+}
@@ -0,0 +1,362 @@
+#![allow(dead_code)]
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Coordinate is an integer type usable for the coordinates of a point.
+pub trait Coordinate: Copy + Ord + Default + Add<Output = Self> + Sub<Output = Self> {
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    /// Returns the distance between the two values, which fits in the type
+    /// unlike for `abs_diff` on signed integers.
+    fn distance(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+
+    /// Moves one unit in the direction of the sign of `step`, if the result
+    /// fits in the type.
+    fn step(self, step: i8) -> Option<Self> {
+        match step {
+            0 => Some(self),
+            1.. => self.checked_add(Self::ONE),
+            _ => self.checked_sub(Self::ONE),
+        }
+    }
+}
+
+macro_rules! impl_coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                const ONE: Self = 1;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_coordinate!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// Point2 is a point or a vector of the plane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// Point3 is a point or a vector of space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// Implements the operations shared by the points of any dimension.
+macro_rules! impl_point {
+    ($point:ident { $($axis:ident),* }) => {
+        impl<T: Coordinate> $point<T> {
+            pub const fn new($($axis: T),*) -> Self {
+                Self { $($axis),* }
+            }
+
+            /// Returns the sum of the distances along each axis.
+            pub fn manhattan(self, other: Self) -> T {
+                let mut distance = T::default();
+                $(distance = distance + self.$axis.distance(other.$axis);)*
+                distance
+            }
+
+            /// Returns the largest of the distances along each axis, that is
+            /// the number of king moves between the points.
+            pub fn chebyshev(self, other: Self) -> T {
+                let mut distance = T::default();
+                $(distance = distance.max(self.$axis.distance(other.$axis));)*
+                distance
+            }
+
+            /// Returns the smallest coordinates of both points on each axis.
+            pub fn min(self, other: Self) -> Self {
+                Self { $($axis: self.$axis.min(other.$axis)),* }
+            }
+
+            /// Returns the largest coordinates of both points on each axis.
+            pub fn max(self, other: Self) -> Self {
+                Self { $($axis: self.$axis.max(other.$axis)),* }
+            }
+
+            /// Returns the lowest and the highest corners of the smallest box
+            /// holding all the points, if any.
+            pub fn bounding_box<I>(points: I) -> Option<(Self, Self)>
+            where
+                I: IntoIterator<Item = Self>,
+            {
+                let mut points = points.into_iter();
+                let first = points.next()?;
+                Some(points.fold((first, first), |(min, max), point| {
+                    (min.min(point), max.max(point))
+                }))
+            }
+
+            /// Returns the point moved by one unit along each axis where
+            /// `steps` is not zero, if it fits in the type.
+            fn step(self, steps: &[i8]) -> Option<Self> {
+                let mut steps = steps.iter();
+                Some(Self { $($axis: self.$axis.step(*steps.next()?)?),* })
+            }
+        }
+
+        impl<T: Add<Output = T>> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self { $($axis: self.$axis + other.$axis),* }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self { $($axis: self.$axis - other.$axis),* }
+            }
+        }
+
+        impl<T: Copy + Add<Output = T>> AddAssign for $point<T> {
+            fn add_assign(&mut self, other: Self) {
+                $(self.$axis = self.$axis + other.$axis;)*
+            }
+        }
+
+        impl<T: Copy + Sub<Output = T>> SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: Self) {
+                $(self.$axis = self.$axis - other.$axis;)*
+            }
+        }
+
+        /// Scales the vector by the factor.
+        impl<T: Copy + Mul<Output = T>> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, factor: T) -> Self {
+                Self { $($axis: self.$axis * factor),* }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($axis: -self.$axis),* }
+            }
+        }
+    };
+}
+
+impl_point!(Point2 { x, y });
+impl_point!(Point3 { x, y, z });
+
+impl<T: Coordinate> Point2<T> {
+    /// Returns the points above, left, right and below, leaving out those
+    /// outside the range of the type.
+    pub fn neighbours4(self) -> impl Iterator<Item = Self> {
+        [[0, -1], [-1, 0], [1, 0], [0, 1]]
+            .into_iter()
+            .filter_map(move |steps| self.step(&steps))
+    }
+
+    /// Returns the points around, diagonals included, leaving out those
+    /// outside the range of the type.
+    pub fn neighbours8(self) -> impl Iterator<Item = Self> {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| [dx, dy]))
+            .filter(|&steps| steps != [0, 0])
+            .filter_map(move |steps| self.step(&steps))
+    }
+}
+
+impl<T: Coordinate> Point3<T> {
+    /// Returns the points sharing a face with this one, leaving out those
+    /// outside the range of the type.
+    pub fn neighbours6(self) -> impl Iterator<Item = Self> {
+        [
+            [-1, 0, 0],
+            [1, 0, 0],
+            [0, -1, 0],
+            [0, 1, 0],
+            [0, 0, -1],
+            [0, 0, 1],
+        ]
+        .into_iter()
+        .filter_map(move |steps| self.step(&steps))
+    }
+
+    /// Returns the points sharing a face, an edge or a corner with this one,
+    /// leaving out those outside the range of the type.
+    pub fn neighbours26(self) -> impl Iterator<Item = Self> {
+        (-1..=1)
+            .flat_map(|dz| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dx| [dx, dy, dz])))
+            .filter(|&steps| steps != [0, 0, 0])
+            .filter_map(move |steps| self.step(&steps))
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(point: Point2<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl<T> From<[T; 3]> for Point3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T> From<Point3<T>> for [T; 3] {
+    fn from(point: Point3<T>) -> Self {
+        [point.x, point.y, point.z]
+    }
+}
+
+/// Writes the point as `x,y`, the way puzzles usually list them.
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+/// Rotation is one of the 24 proper rotations of 3D space, expressed as a
+/// signed permutation of the axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rotation {
+    axes: [usize; 3],
+    negated: [bool; 3],
+}
+
+impl Rotation {
+    /// Returns all the 24 rotations: the signed axis permutations with a
+    /// determinant of 1 (the other 24 are reflections). The identity comes
+    /// first.
+    pub fn all() -> Vec<Self> {
+        let permutations = [
+            ([0, 1, 2], false),
+            ([1, 2, 0], false),
+            ([2, 0, 1], false),
+            ([0, 2, 1], true),
+            ([2, 1, 0], true),
+            ([1, 0, 2], true),
+        ];
+
+        let mut rotations = Vec::with_capacity(24);
+        for (axes, odd) in permutations {
+            for signs in 0..8 {
+                let negated = [0, 1, 2].map(|bit| signs & (1 << bit) != 0);
+                let odd_negations = negated.iter().filter(|&&negated| negated).count() % 2 == 1;
+                if odd == odd_negations {
+                    rotations.push(Self { axes, negated });
+                }
+            }
+        }
+
+        rotations
+    }
+
+    pub fn apply<T: Copy + Neg<Output = T>>(&self, point: Point3<T>) -> Point3<T> {
+        let coordinates: [T; 3] = point.into();
+        Point3::from([0, 1, 2].map(|axis| {
+            let coordinate = coordinates[self.axes[axis]];
+            if self.negated[axis] {
+                -coordinate
+            } else {
+                coordinate
+            }
+        }))
+    }
+}
+
+#[test]
+pub fn test_arithmetic() {
+    let a = Point2::new(3, -2);
+    let b = Point2::new(-1, 5);
+    assert_eq!(a + b, Point2::new(2, 3));
+    assert_eq!(a - b, Point2::new(4, -7));
+    assert_eq!(-a * 2, Point2::new(-6, 4));
+    assert_eq!((a.manhattan(b), a.chebyshev(b)), (11, 7));
+
+    let mut c = Point3::new(1_u8, 2, 3);
+    c += Point3::new(1, 1, 1);
+    assert_eq!(c.to_string(), "2,3,4");
+    assert_eq!(c.manhattan(Point3::new(4, 3, 0)), 6);
+
+    assert_eq!(
+        Point2::bounding_box([a, b, Point2::new(0, 9)]),
+        Some((Point2::new(-1, -2), Point2::new(3, 9)))
+    );
+    assert_eq!(Point3::<i32>::bounding_box([]), None);
+}
+
+#[test]
+pub fn test_neighbours() {
+    let corner = Point2::new(0_usize, 0);
+    assert_eq!(
+        corner.neighbours4().collect::<Vec<_>>(),
+        [Point2::new(1, 0), Point2::new(0, 1)]
+    );
+    assert_eq!(corner.neighbours8().count(), 3);
+    assert_eq!(Point2::new(5_i32, 5).neighbours8().count(), 8);
+
+    let origin = Point3::new(0_i64, 0, 0);
+    assert_eq!(origin.neighbours6().count(), 6);
+    assert!(origin
+        .neighbours26()
+        .all(|neighbour| origin.chebyshev(neighbour) == 1));
+    assert_eq!(origin.neighbours26().count(), 26);
+}
+
+#[test]
+pub fn test_rotations() {
+    use std::collections::HashSet;
+
+    let rotations = Rotation::all();
+    let images = rotations
+        .iter()
+        .map(|r| r.apply(Point3::new(1, 2, 3)))
+        .collect::<HashSet<_>>();
+
+    assert_eq!(rotations.len(), 24);
+    assert_eq!(images.len(), 24);
+    assert_eq!(
+        rotations[0].apply(Point3::new(1, 2, 3)),
+        Point3::new(1, 2, 3)
+    );
+    assert!(images.contains(&Point3::new(-2, 1, 3)));
+    // a mirror image is not a rotation
+    assert!(!images.contains(&Point3::new(-1, 2, 3)));
+}
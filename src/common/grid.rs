@@ -0,0 +1,339 @@
+#![allow(dead_code)]
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::common::parse_lines::{lines, Span};
+use crate::error::AocError;
+
+/// Position of a cell, as `(x, y)` with `y` growing downwards.
+pub type Position = (usize, usize);
+
+/// Grid is a rectangular map of cells stored row after row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid by calling `f` on each position, row after row.
+    pub fn from_fn<F: FnMut(Position) -> T>(width: usize, height: usize, mut f: F) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut f)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses a map with one row per non-blank line, converting each
+    /// character with `f`. Rows may be indented, and must all have the same
+    /// length. A text without rows gives an empty grid.
+    pub fn parse_with<F: Fn(char) -> Option<T>>(text: &str, f: F) -> Result<Self, AocError> {
+        Self::parse_lines(lines(text), f)
+    }
+
+    /// Like [Grid::parse_with], for lines already split from the input, so
+    /// that a map following other sections reports its place in the whole
+    /// input.
+    pub fn parse_lines<'a, I, F>(lines: I, f: F) -> Result<Self, AocError>
+    where
+        I: IntoIterator<Item = Span<'a>>,
+        F: Fn(char) -> Option<T>,
+    {
+        let mut width = 0;
+        let mut cells = Vec::new();
+
+        for line in lines.into_iter().map(Span::trim) {
+            if line.text.is_empty() {
+                continue;
+            }
+
+            let row = line
+                .text
+                .chars()
+                .enumerate()
+                .map(|(i, c)| {
+                    f(c).ok_or_else(|| {
+                        let column = line.column + i;
+                        AocError::parse(line.line, column, &c.to_string(), "Invalid character")
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if cells.is_empty() {
+                width = row.len();
+            } else if row.len() != width {
+                let message = format!("Expected {} cells, found {}", width, row.len());
+                return Err(line.error(&message));
+            }
+            cells.extend(row);
+        }
+
+        Ok(Self {
+            width,
+            height: cells.len().checked_div(width).unwrap_or(0),
+            cells,
+        })
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the number of cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub const fn contains(&self, (x, y): Position) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Returns the cell at the position, if inside the grid.
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.index_of(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.index_of(position).map(|index| &mut self.cells[index])
+    }
+
+    fn index_of(&self, position: Position) -> Option<usize> {
+        let (x, y) = position;
+        self.contains(position).then(|| y * self.width + x)
+    }
+
+    /// Returns every position, row after row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Returns every cell with its position, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on a zero size, which only an empty grid has
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    /// Returns the positions above, left, right and below, inside the grid.
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        self.offset_positions(position, &[(0, -1), (-1, 0), (1, 0), (0, 1)])
+    }
+
+    /// Returns the positions around, diagonals included, inside the grid.
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        const OFFSETS: [(isize, isize); 8] = [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ];
+        self.offset_positions(position, &OFFSETS)
+    }
+
+    fn offset_positions(
+        &self,
+        (x, y): Position,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Position> + '_ {
+        offsets
+            .iter()
+            .filter_map(move |&(dx, dy)| {
+                Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?))
+            })
+            .filter(|&position| self.contains(position))
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Repeats the grid `across` times to the right and `down` times
+    /// downwards, calling `f` on each cell with the `(x, y)` index of its
+    /// copy.
+    pub fn tile<U, F>(&self, across: usize, down: usize, f: F) -> Grid<U>
+    where
+        F: Fn(&T, Position) -> U,
+    {
+        Grid::from_fn(self.width * across, self.height * down, |(x, y)| {
+            let cell = &self[(x % self.width, y % self.height)];
+            f(cell, (x / self.width, y / self.height))
+        })
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Builds a grid with every cell set to the value.
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Grows the grid by `border` cells on each side, set to `fill`.
+    pub fn expand(&self, border: usize, fill: T) -> Self {
+        Self::from_fn(
+            self.width + 2 * border,
+            self.height + 2 * border,
+            |(x, y)| match (x.checked_sub(border), y.checked_sub(border)) {
+                (Some(x), Some(y)) => self.get((x, y)).unwrap_or(&fill).clone(),
+                _ => fill.clone(),
+            },
+        )
+    }
+}
+
+impl Grid<u8> {
+    /// Parses a map of decimal digits.
+    pub fn parse_digits(text: &str) -> Result<Self, AocError> {
+        // to_digit(10) is always below 10
+        Self::parse_with(text, |c| c.to_digit(10).map(|digit| digit as u8))
+    }
+}
+
+impl Grid<char> {
+    /// Parses a map of characters, keeping them as they are.
+    pub fn parse_chars(text: &str) -> Result<Self, AocError> {
+        Self::parse_with(text, Some)
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    /// Returns the cell at the position, panicking outside the grid.
+    fn index(&self, position: Position) -> &T {
+        self.get(position)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", position))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", position))
+    }
+}
+
+/// Writes each row on its own line, without a trailing newline.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+pub fn test_parse() -> Result<(), AocError> {
+    let grid = Grid::parse_digits("123\n  456\n\n")?;
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[(2, 1)], 6);
+    assert_eq!(grid.get((3, 0)), None);
+    assert_eq!(grid.to_string(), "123\n456");
+
+    assert_eq!(
+        Grid::parse_digits("123\n  4x6"),
+        Err(AocError::parse(2, 4, "x", "Invalid character"))
+    );
+    assert_eq!(
+        Grid::parse_chars("#.#\n##"),
+        Err(AocError::parse(2, 1, "##", "Expected 3 cells, found 2"))
+    );
+    assert!(Grid::parse_chars("\n  \n")?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+pub fn test_neighbours() -> Result<(), AocError> {
+    let grid = Grid::parse_chars("abc\ndef\nghi")?;
+    let cells = |positions: Vec<Position>| {
+        positions
+            .into_iter()
+            .map(|position| grid[position])
+            .collect::<String>()
+    };
+
+    assert_eq!(cells(grid.neighbours4((1, 1)).collect()), "bdfh");
+    assert_eq!(cells(grid.neighbours4((0, 0)).collect()), "bd");
+    assert_eq!(cells(grid.neighbours8((1, 1)).collect()), "abcdfghi");
+    assert_eq!(cells(grid.neighbours8((2, 2)).collect()), "efh");
+
+    Ok(())
+}
+
+#[test]
+pub fn test_rows_and_columns() -> Result<(), AocError> {
+    let grid = Grid::parse_chars("ab\ncd\nef")?;
+    let rows = grid
+        .rows()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>();
+    assert_eq!(rows, ["ab", "cd", "ef"]);
+    let columns = grid
+        .columns()
+        .map(|column| column.collect::<String>())
+        .collect::<Vec<_>>();
+    assert_eq!(columns, ["ace", "bdf"]);
+
+    Ok(())
+}
+
+#[test]
+pub fn test_tile_and_expand() -> Result<(), AocError> {
+    let grid = Grid::parse_digits("12\n34")?;
+    let tiled = grid.tile(2, 2, |&n, (tile_x, tile_y)| n + (tile_x + 2 * tile_y) as u8);
+    assert_eq!(tiled.to_string(), "1223\n3445\n3445\n5667");
+
+    assert_eq!(grid.expand(1, 0).to_string(), "0000\n0120\n0340\n0000");
+    assert_eq!(Grid::filled(2, 1, '.').to_string(), "..");
+
+    Ok(())
+}
@@ -0,0 +1,404 @@
+#![allow(dead_code)]
+
+use std::ops::RangeInclusive;
+
+/// Returns the number of integers in the range, saturating at `u64::MAX` as
+/// the range of every `i64` holds one more.
+fn range_len(range: &RangeInclusive<i64>) -> u64 {
+    if range.is_empty() {
+        0
+    } else {
+        range.end().abs_diff(*range.start()).saturating_add(1)
+    }
+}
+
+/// IntervalSet is a set of integers stored as sorted, disjoint and
+/// non-adjacent inclusive ranges, so that large ranges cost as little as
+/// small ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<RangeInclusive<i64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the ranges of the set, in increasing order.
+    pub fn ranges(&self) -> &[RangeInclusive<i64>] {
+        &self.ranges
+    }
+
+    /// Returns the number of integers in the set, saturating at `u64::MAX`.
+    pub fn len(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(range_len)
+            .fold(0, u64::saturating_add)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        // the ranges are sorted, so only the last one starting before the
+        // value may hold it
+        let index = self.ranges.partition_point(|range| *range.start() <= value);
+        index > 0 && value <= *self.ranges[index - 1].end()
+    }
+
+    /// Adds the integers of the range to the set.
+    pub fn insert(&mut self, range: RangeInclusive<i64>) {
+        *self = self.union(&Self::from_iter([range]));
+    }
+
+    /// Removes the integers of the range from the set.
+    pub fn remove(&mut self, range: RangeInclusive<i64>) {
+        *self = self.difference(&Self::from_iter([range]));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges
+            .iter()
+            .chain(other.ranges.iter())
+            .cloned()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let overlap = *a.start().max(b.start())..=*a.end().min(b.end());
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            // the range ending first cannot overlap any later range
+            if a.end() < b.end() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut holes = other.ranges.iter().peekable();
+        for range in self.ranges.iter() {
+            // the first value of the range not yet known to be in a hole
+            let mut start = Some(*range.start());
+            while let (Some(from), Some(hole)) = (start, holes.peek()) {
+                if hole.start() > range.end() {
+                    break;
+                }
+                if *hole.start() > from {
+                    ranges.push(from..=*hole.start() - 1);
+                }
+                // a hole going past the range may also cover the next one
+                if hole.end() >= range.end() {
+                    start = None;
+                } else {
+                    start = Some(from.max(*hole.end() + 1));
+                    holes.next();
+                }
+            }
+            if let Some(from) = start {
+                ranges.push(from..=*range.end());
+            }
+        }
+        Self { ranges }
+    }
+}
+
+/// Builds the set holding the integers of every range, which may overlap and
+/// come in any order.
+impl FromIterator<RangeInclusive<i64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<i64>>>(iter: I) -> Self {
+        let mut sorted = iter
+            .into_iter()
+            .filter(|range| !range.is_empty())
+            .collect::<Vec<_>>();
+        sorted.sort_unstable_by_key(|range| *range.start());
+
+        let mut ranges: Vec<RangeInclusive<i64>> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match ranges.last_mut() {
+                Some(last) if *range.start() <= last.end().saturating_add(1) => {
+                    *last = *last.start()..=*last.end().max(range.end());
+                }
+                _ => ranges.push(range),
+            }
+        }
+        Self { ranges }
+    }
+}
+
+/// Cuboid is an axis-aligned box of `N` dimensions, bounds included. It is
+/// never empty: operations that could give an empty one return an `Option`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid<const N: usize> {
+    pub min: [i64; N],
+    pub max: [i64; N],
+}
+
+impl<const N: usize> Cuboid<N> {
+    pub const fn new(min: [i64; N], max: [i64; N]) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns the cuboid spanning the ranges, one per axis, if none is
+    /// empty.
+    pub fn from_ranges(ranges: [RangeInclusive<i64>; N]) -> Option<Self> {
+        if ranges.iter().any(RangeInclusive::is_empty) {
+            return None;
+        }
+        Some(Self {
+            min: ranges.clone().map(|range| *range.start()),
+            max: ranges.map(|range| *range.end()),
+        })
+    }
+
+    /// Returns the number of integer points in the cuboid, or `None` if it
+    /// does not fit in a `u64`.
+    pub fn volume(&self) -> Option<u64> {
+        (0..N).try_fold(1_u64, |volume, axis| {
+            let len = self.max[axis].abs_diff(self.min[axis]).checked_add(1)?;
+            volume.checked_mul(len)
+        })
+    }
+
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        (0..N).all(|axis| (self.min[axis]..=self.max[axis]).contains(&point[axis]))
+    }
+
+    /// Returns the points belonging to both cuboids, if any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = std::array::from_fn(|axis| self.min[axis].max(other.min[axis]));
+        let max = std::array::from_fn(|axis| self.max[axis].min(other.max[axis]));
+
+        if (0..N).all(|axis| min[axis] <= max[axis]) {
+            Some(Self { min, max })
+        } else {
+            None
+        }
+    }
+
+    /// Returns up to `2 * N` disjoint cuboids covering the points of `self`
+    /// that are not in `other`.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let hole = match self.intersection(other) {
+            Some(hole) => hole,
+            None => return vec![*self],
+        };
+
+        // slice off the slabs before and after the hole one axis at a time,
+        // shrinking the remainder down to the hole itself
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for axis in 0..N {
+            if rest.min[axis] < hole.min[axis] {
+                let mut below = rest;
+                below.max[axis] = hole.min[axis] - 1;
+                pieces.push(below);
+                rest.min[axis] = hole.min[axis];
+            }
+            if rest.max[axis] > hole.max[axis] {
+                let mut above = rest;
+                above.min[axis] = hole.max[axis] + 1;
+                pieces.push(above);
+                rest.max[axis] = hole.max[axis];
+            }
+        }
+
+        pieces
+    }
+}
+
+/// CuboidSet is a set of points stored as disjoint cuboids, so that its
+/// volume is the sum of theirs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CuboidSet<const N: usize> {
+    cuboids: Vec<Cuboid<N>>,
+}
+
+impl<const N: usize> Default for CuboidSet<N> {
+    fn default() -> Self {
+        Self {
+            cuboids: Vec::new(),
+        }
+    }
+}
+
+impl<const N: usize> CuboidSet<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns disjoint cuboids covering the set, in no particular order.
+    pub fn cuboids(&self) -> &[Cuboid<N>] {
+        &self.cuboids
+    }
+
+    /// Returns the number of points in the set, or `None` if it does not fit
+    /// in a `u64`.
+    pub fn volume(&self) -> Option<u64> {
+        self.cuboids
+            .iter()
+            .try_fold(0_u64, |volume, cuboid| volume.checked_add(cuboid.volume()?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cuboids.is_empty()
+    }
+
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        self.cuboids.iter().any(|cuboid| cuboid.contains(point))
+    }
+
+    /// Adds the points of the cuboid to the set.
+    pub fn insert(&mut self, cuboid: Cuboid<N>) {
+        self.remove(cuboid);
+        self.cuboids.push(cuboid);
+    }
+
+    /// Removes the points of the cuboid from the set.
+    pub fn remove(&mut self, cuboid: Cuboid<N>) {
+        self.cuboids = self
+            .cuboids
+            .iter()
+            .flat_map(|c| c.subtract(&cuboid))
+            .collect();
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for &cuboid in other.cuboids.iter() {
+            union.insert(cuboid);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        // the pieces of disjoint cuboids are disjoint as well
+        let cuboids = self
+            .cuboids
+            .iter()
+            .flat_map(|a| other.cuboids.iter().filter_map(|b| a.intersection(b)))
+            .collect();
+        Self { cuboids }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for &cuboid in other.cuboids.iter() {
+            difference.remove(cuboid);
+        }
+        difference
+    }
+}
+
+impl<const N: usize> FromIterator<Cuboid<N>> for CuboidSet<N> {
+    fn from_iter<I: IntoIterator<Item = Cuboid<N>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for cuboid in iter {
+            set.insert(cuboid);
+        }
+        set
+    }
+}
+
+#[test]
+pub fn test_interval_set() {
+    let a = IntervalSet::from_iter([5..=8, 1..=3, 4..=4, 12..=20]);
+    assert_eq!(a.ranges(), [1..=8, 12..=20]);
+    assert_eq!(a.len(), 17);
+    assert!(a.contains(12) && !a.contains(10) && !a.contains(0));
+
+    let b = IntervalSet::from_iter([0..=1, 7..=14, 18..=18]);
+    assert_eq!(a.union(&b).ranges(), [0..=20]);
+    assert_eq!(
+        a.intersection(&b).ranges(),
+        [1..=1, 7..=8, 12..=14, 18..=18]
+    );
+    assert_eq!(a.difference(&b).ranges(), [2..=6, 15..=17, 19..=20]);
+    assert_eq!(b.difference(&a).ranges(), [0..=0, 9..=11]);
+
+    let mut c = IntervalSet::new();
+    c.insert(-5..=5);
+    c.remove(0..=0);
+    c.remove(5..=9);
+    assert_eq!(c.ranges(), [-5..=-1, 1..=4]);
+    c.remove(i64::MIN..=i64::MAX);
+    assert!(c.is_empty());
+    assert_eq!(
+        IntervalSet::from_iter([i64::MIN..=i64::MAX]).len(),
+        u64::MAX
+    );
+}
+
+#[test]
+pub fn test_cuboid() {
+    let cube = Cuboid::new([0; 3], [2; 3]);
+    let corner = Cuboid::new([2; 3], [5; 3]);
+    let center = Cuboid::new([1; 3], [1; 3]);
+
+    assert_eq!(cube.volume(), Some(27));
+    assert_eq!(
+        cube.intersection(&corner),
+        Some(Cuboid::new([2; 3], [2; 3]))
+    );
+    assert_eq!(cube.intersection(&Cuboid::new([3; 3], [4; 3])), None);
+
+    let hollow = cube.subtract(&center);
+    assert_eq!(hollow.len(), 6);
+    assert_eq!(
+        hollow.iter().map(Cuboid::volume).sum::<Option<u64>>(),
+        Some(26)
+    );
+    assert!(hollow.iter().all(|c| c.intersection(&center).is_none()));
+
+    assert_eq!(
+        cube.subtract(&corner)
+            .iter()
+            .map(Cuboid::volume)
+            .sum::<Option<u64>>(),
+        Some(26)
+    );
+    assert_eq!(cube.subtract(&cube), vec![]);
+    let huge = Cuboid::new([-9_000_000; 3], [9_000_000; 3]);
+    assert_eq!(huge.volume(), None);
+    assert_eq!(Cuboid::new([i64::MIN], [i64::MAX]).volume(), None);
+    assert_eq!(
+        Cuboid::new([i64::MIN], [i64::MAX - 1]).volume(),
+        Some(u64::MAX)
+    );
+    assert_eq!(
+        Cuboid::from_ranges([0..=3, 2..=2]),
+        Some(Cuboid::new([0, 2], [3, 2]))
+    );
+    // a range read backwards from the input is empty
+    let (from, to) = (2, 1);
+    assert_eq!(Cuboid::from_ranges([0..=3, from..=to]), None);
+}
+
+#[test]
+pub fn test_cuboid_set() {
+    let squares = CuboidSet::from_iter([Cuboid::new([0, 0], [3, 3]), Cuboid::new([2, 2], [5, 5])]);
+    assert_eq!(squares.volume(), Some(16 + 16 - 4));
+    assert!(squares.contains([4, 5]) && !squares.contains([4, 1]));
+
+    let band = CuboidSet::from_iter([Cuboid::new([-10, 1], [10, 2])]);
+    assert_eq!(squares.intersection(&band).volume(), Some(4 + 6));
+    assert_eq!(squares.difference(&band).volume(), Some(28 - 10));
+    assert_eq!(squares.union(&band).volume(), Some(28 + 42 - 10));
+
+    let halves =
+        CuboidSet::from_iter([Cuboid::new([i64::MIN], [-1]), Cuboid::new([0], [i64::MAX])]);
+    assert_eq!(halves.volume(), None);
+    assert!(squares.difference(&squares).is_empty());
+}
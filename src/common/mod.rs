@@ -1,3 +1,8 @@
+pub mod bits;
+pub mod geometry;
+pub mod grid;
+pub mod intervals;
 pub mod parse_lines;
 pub mod recognize_chars;
+pub mod search;
 pub use parse_lines::parse_lines;
@@ -1,29 +1,180 @@
+#![allow(dead_code)]
+
+use std::any::type_name;
+use std::ops::RangeInclusive;
 use std::str::FromStr;
 
-pub fn parse_lines<T: FromStr>(input: &str) -> Result<Vec<T>, String> {
-    let f = |i: usize, line: &str| {
-        line.parse::<T>()
-            .map_err(|_| format!("Line {}: Not a valid integer {}", i + 1, line))
-    };
+use crate::error::AocError;
 
-    map_lines(input, f)
+/// Span is a piece of the input along with the 1-based line and column where
+/// it starts, so that errors point at the original text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span<'a> {
+    pub line: usize,
+    pub column: usize,
+    pub text: &'a str,
 }
 
-pub fn map_lines<T, F>(input: &str, f: F) -> Result<Vec<T>, String>
-where
-    F: Fn(usize, &str) -> Result<T, String>,
-{
+impl<'a> Span<'a> {
+    /// Returns a parse error located at the span.
+    pub fn error(&self, message: &str) -> AocError {
+        AocError::parse(self.line, self.column, self.text, message)
+    }
+
+    /// Returns the part of the span starting at the byte offset.
+    fn skip(self, offset: usize) -> Self {
+        Self {
+            line: self.line,
+            column: self.column + self.text[..offset].chars().count(),
+            text: &self.text[offset..],
+        }
+    }
+
+    pub fn trim(self) -> Self {
+        let trimmed = self.text.trim_start();
+        let start = self.skip(self.text.len() - trimmed.len());
+        Self {
+            text: trimmed.trim_end(),
+            ..start
+        }
+    }
+
+    /// Splits the span at the first occurrence of the separator.
+    pub fn split_once(self, separator: &str) -> Option<(Self, Self)> {
+        let offset = self.text.find(separator)?;
+        let left = Self {
+            text: &self.text[..offset],
+            ..self
+        };
+        Some((left, self.skip(offset + separator.len())))
+    }
+
+    /// Returns the span without the prefix, if it starts with it.
+    pub fn strip_prefix(self, prefix: &str) -> Option<Self> {
+        self.text
+            .starts_with(prefix)
+            .then(|| self.skip(prefix.len()))
+    }
+
+    /// Returns the span without the suffix, if it ends with it.
+    pub fn strip_suffix(self, suffix: &str) -> Option<Self> {
+        let text = self.text.strip_suffix(suffix)?;
+        Some(Self { text, ..self })
+    }
+
+    /// Parses the span, reporting the expected type on failure.
+    pub fn parse<T: FromStr>(&self) -> Result<T, AocError> {
+        self.text.parse::<T>().map_err(|_| {
+            // keep `u8` out of `core::primitive::u8` or `my::Type` out of a path
+            let name = type_name::<T>().rsplit("::").next().unwrap_or_default();
+            self.error(&format!("Not a valid {}", name))
+        })
+    }
+
+    /// Returns the words of the span, separated by commas or whitespace.
+    pub fn words(self) -> impl Iterator<Item = Self> {
+        self.text
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|word| !word.is_empty())
+            .map(move |word| {
+                let offset = word.as_ptr() as usize - self.text.as_ptr() as usize;
+                Self {
+                    text: word,
+                    ..self.skip(offset)
+                }
+            })
+    }
+
+    /// Parses a list of numbers separated by commas or whitespace.
+    pub fn numbers<T: FromStr>(self) -> Result<Vec<T>, AocError> {
+        self.words().map(|word| word.parse()).collect()
+    }
+
+    /// Splits the span in two around the separator, such as `-` in `a-b` or
+    /// `->` in `a -> b`, trimming both sides.
+    pub fn pair(self, separator: &str) -> Result<(Self, Self), AocError> {
+        let (left, right) = self
+            .split_once(separator)
+            .ok_or_else(|| self.error(&format!("Expected {}", separator.trim())))?;
+        Ok((left.trim(), right.trim()))
+    }
+
+    /// Returns the value of a `key=value` span.
+    pub fn value_of(self, key: &str) -> Result<Self, AocError> {
+        let trimmed = self.trim();
+        trimmed
+            .strip_prefix(key)
+            .and_then(|rest| rest.trim().strip_prefix("="))
+            .map(Span::trim)
+            .ok_or_else(|| trimmed.error(&format!("Expected {}=", key)))
+    }
+
+    /// Parses a `key=a..b` span to the inclusive range `a..=b`.
+    pub fn range<T: FromStr>(self, key: &str) -> Result<RangeInclusive<T>, AocError> {
+        let (start, end) = self.value_of(key)?.pair("..")?;
+        Ok(start.parse()?..=end.parse()?)
+    }
+}
+
+/// Returns the non-blank lines of the input, trimmed.
+pub fn lines(input: &str) -> impl Iterator<Item = Span<'_>> {
     input
         .lines()
         .enumerate()
-        .map(|(idx, line)| (idx, line.trim()))
-        .filter(|(_, line)| !line.is_empty())
-        .map(|(idx, line)| f(idx, line))
+        .map(|(i, line)| {
+            Span {
+                line: i + 1,
+                column: 1,
+                text: line,
+            }
+            .trim()
+        })
+        .filter(|line| !line.text.is_empty())
+}
+
+/// Splits the input into sections of non-blank lines, separated by blank
+/// lines.
+pub fn sections(input: &str) -> Vec<Vec<Span<'_>>> {
+    let mut sections: Vec<Vec<Span>> = Vec::new();
+    let mut previous_line = 0;
+    for line in lines(input) {
+        match sections.last_mut() {
+            Some(section) if line.line == previous_line + 1 => section.push(line),
+            _ => sections.push(vec![line]),
+        }
+        previous_line = line.line;
+    }
+    sections
+}
+
+/// Parses every number of the input, separated by commas, whitespace or
+/// line breaks.
+pub fn numbers<T: FromStr>(input: &str) -> Result<Vec<T>, AocError> {
+    let mut numbers = Vec::new();
+    for line in lines(input) {
+        numbers.extend(line.numbers::<T>()?);
+    }
+    Ok(numbers)
+}
+
+pub fn parse_lines<T: FromStr>(input: &str) -> Result<Vec<T>, AocError> {
+    lines(input).map(|line| line.parse()).collect()
+}
+
+/// Applies `f` to the 1-based number and trimmed content of each non-blank
+/// line. Errors of `f` that are not an [AocError] become invalid input errors.
+pub fn map_lines<T, E, F>(input: &str, f: F) -> Result<Vec<T>, AocError>
+where
+    E: Into<AocError>,
+    F: Fn(usize, &str) -> Result<T, E>,
+{
+    lines(input)
+        .map(|line| f(line.line, line.text).map_err(Into::into))
         .collect()
 }
 
 #[test]
-pub fn test_map_lines() -> Result<(), String> {
+pub fn test_map_lines() -> Result<(), AocError> {
     let lines = "1\n2\n3\n";
 
     let parse_and_increment = |i: usize, line: &str| {
@@ -38,11 +189,18 @@ pub fn test_map_lines() -> Result<(), String> {
         .zip(expected)
         .for_each(|(&l, r)| assert_eq!(l, r));
 
+    assert_eq!(
+        map_lines("1\n\nx", parse_and_increment),
+        Err(AocError::InvalidInput(
+            "error on 3 with value x".to_string()
+        ))
+    );
+
     Ok(())
 }
 
 #[test]
-pub fn test_parse_lines() -> Result<(), String> {
+pub fn test_parse_lines() -> Result<(), AocError> {
     let lines = "1\n2\n3\n";
 
     let nums = parse_lines::<u8>(lines)?;
@@ -52,5 +210,90 @@ pub fn test_parse_lines() -> Result<(), String> {
         .zip(expected)
         .for_each(|(&l, r)| assert_eq!(l, r));
 
+    assert_eq!(
+        parse_lines::<u8>("1\n\n  x2\n"),
+        Err(AocError::parse(3, 3, "x2", "Not a valid u8"))
+    );
+
+    Ok(())
+}
+
+#[test]
+pub fn test_sections() -> Result<(), AocError> {
+    let input = "7,4,9\n\n  22 13\n   8  2\n\n\n1 -> 2\n";
+    let sections = sections(input);
+    assert_eq!(sections.len(), 3);
+
+    assert_eq!(sections[0][0].numbers::<u8>()?, [7, 4, 9]);
+    assert_eq!(sections[1][1].numbers::<u8>()?, [8, 2]);
+    assert_eq!(
+        sections[1][1].words().nth(1),
+        Some(Span {
+            line: 4,
+            column: 7,
+            text: "2"
+        })
+    );
+    assert_eq!(
+        lines(" 1, 300")
+            .next()
+            .ok_or("missing line")?
+            .numbers::<u8>(),
+        Err(AocError::parse(1, 5, "300", "Not a valid u8"))
+    );
+
+    let (from, to) = sections[2][0].pair("->")?;
+    assert_eq!((from.text, to.text, to.column), ("1", "2", 6));
+    assert_eq!(
+        sections[2][0].pair("-"),
+        Ok((
+            Span {
+                line: 7,
+                column: 1,
+                text: "1"
+            },
+            Span {
+                line: 7,
+                column: 4,
+                text: "> 2"
+            }
+        ))
+    );
+    assert_eq!(
+        sections[2][0].pair(","),
+        Err(AocError::parse(7, 1, "1 -> 2", "Expected ,"))
+    );
+
+    Ok(())
+}
+
+#[test]
+pub fn test_ranges() -> Result<(), AocError> {
+    let (x, y) = lines("target area: x=20..30, y=-10..-5")
+        .next()
+        .and_then(|line| line.strip_prefix("target area: "))
+        .ok_or("missing line")?
+        .pair(",")?;
+    assert_eq!(x.range::<i32>("x")?, 20..=30);
+    assert_eq!(y.range::<i32>("y")?, -10..=-5);
+    assert_eq!(
+        y.range::<i32>("x"),
+        Err(AocError::parse(1, 24, "y=-10..-5", "Expected x="))
+    );
+    assert_eq!(
+        lines("x=1-3")
+            .next()
+            .ok_or("missing line")?
+            .range::<u8>("x"),
+        Err(AocError::parse(1, 3, "1-3", "Expected .."))
+    );
+    assert_eq!(
+        lines("x=1..b")
+            .next()
+            .ok_or("missing line")?
+            .range::<u8>("x"),
+        Err(AocError::parse(1, 6, "b", "Not a valid u8"))
+    );
+
     Ok(())
 }
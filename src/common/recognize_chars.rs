@@ -1,25 +1,316 @@
-pub fn recognize(input: &str) -> Result<char, String> {
-    Ok(match input {
-        " ##  \n#  # \n#  # \n#### \n#  # \n#  # " => 'A',
-        "###  \n#  # \n###  \n#  # \n#  # \n###  " => 'B',
-        " ##  \n#  # \n#    \n#    \n#  # \n ##  " => 'C',
-        "#### \n#    \n###  \n#    \n#    \n#### " => 'E',
-        "#### \n#    \n###  \n#    \n#    \n#    " => 'F',
-        " ##  \n#  # \n#    \n# ## \n#  # \n ### " => 'G',
-        "#  # \n#  # \n#### \n#  # \n#  # \n#  # " => 'H',
-        " ### \n  #  \n  #  \n  #  \n  #  \n ### " => 'I',
-        "  ## \n   # \n   # \n   # \n#  # \n ##  " => 'J',
-        "#  # \n# #  \n##   \n# #  \n# #  \n#  # " => 'K',
-        "#    \n#    \n#    \n#    \n#    \n#### " => 'L',
-        " ##  \n#  # \n#  # \n#  # \n#  # \n ##  " => 'O',
-        "###  \n#  # \n#  # \n###  \n#    \n#    " => 'P',
-        "###  \n#  # \n#  # \n###  \n# #  \n#  # " => 'R',
-        " ### \n#    \n#    \n ##  \n   # \n###  " => 'S',
-        "#  # \n#  # \n#  # \n#  # \n#  # \n ##  " => 'U',
-        "#   #\n#   #\n # # \n  #  \n  #  \n  #  " => 'Y',
-        "#### \n   # \n  #  \n #   \n#    \n#### " => 'Z',
-        _ => {
-            return Err(format!("Unrecognized character:\n{}", input));
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+
+use crate::common::grid::Grid;
+use crate::error::AocError;
+
+/// The letters 6 pixels high, 4 or 5 wide, as rows of `#` and `.`.
+const FONT_6: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// The letters 10 pixels high and 6 wide.
+const FONT_10: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+/// Decodes a single letter drawn with `#` for lit pixels, one row per line.
+/// Any other character is a dark pixel.
+pub fn recognize(glyph: &str) -> Result<char, AocError> {
+    let points = glyph.lines().enumerate().flat_map(|(y, row)| {
+        row.chars()
+            .enumerate()
+            .filter(|&(_, c)| c == '#')
+            .map(move |(x, _)| (x, y))
+    });
+    let letters = recognize_points(points)?;
+
+    let mut chars = letters.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None) => Ok(letter),
+        _ => Err(AocError::Unsolvable(format!(
+            "Expected a single character, found {}",
+            letters
+        ))),
+    }
+}
+
+/// Decodes the letters drawn by the lit points, given as `(x, y)` with `y`
+/// growing downwards. Only the relative positions of the points matter.
+pub fn recognize_points<I>(points: I) -> Result<String, AocError>
+where
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    let points = points.into_iter().collect::<HashSet<_>>();
+    let min_x = points.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let min_y = points.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    let width = points
+        .iter()
+        .map(|&(x, _)| x - min_x + 1)
+        .max()
+        .unwrap_or(0);
+    let height = points
+        .iter()
+        .map(|&(_, y)| y - min_y + 1)
+        .max()
+        .unwrap_or(0);
+
+    let banner = Grid::from_fn(width, height, |(x, y)| {
+        points.contains(&(x + min_x, y + min_y))
+    });
+    recognize_banner(&banner)
+}
+
+/// Decodes the letters of a banner drawn with `#` for lit pixels and `.` or
+/// spaces for dark ones, one row per line. Rows may have different lengths.
+pub fn recognize_text(text: &str) -> Result<String, AocError> {
+    let rows = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(j, c)| match c {
+                    '#' => Ok(true),
+                    '.' | ' ' => Ok(false),
+                    _ => Err(AocError::parse(
+                        i + 1,
+                        j + 1,
+                        &c.to_string(),
+                        "Invalid pixel",
+                    )),
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let banner = Grid::from_fn(width, rows.len(), |(x, y)| {
+        rows[y].get(x).copied().unwrap_or(false)
+    });
+    recognize_banner(&banner)
+}
+
+/// Decodes the letters of the banner, splitting them on dark columns. The
+/// font is picked from the height of the lit rows.
+fn recognize_banner(banner: &Grid<bool>) -> Result<String, AocError> {
+    let lit_rows = banner
+        .rows()
+        .enumerate()
+        .filter(|(_, row)| row.contains(&true))
+        .map(|(y, _)| y)
+        .collect::<Vec<_>>();
+    let (top, bottom) = match (lit_rows.first(), lit_rows.last()) {
+        (Some(&top), Some(&bottom)) => (top, bottom),
+        _ => return Err(AocError::Unsolvable("Empty banner".to_string())),
+    };
+    let font = match bottom - top + 1 {
+        6 => FONT_6,
+        10 => FONT_10,
+        height => {
+            return Err(AocError::Unsolvable(format!(
+                "No font is {} pixels high",
+                height
+            )))
+        }
+    };
+
+    let lit_columns = banner
+        .columns()
+        .map(|mut column| column.any(|&lit| lit))
+        .collect::<Vec<_>>();
+    let mut letters = String::new();
+    let mut x = 0;
+    while x < lit_columns.len() {
+        if !lit_columns[x] {
+            x += 1;
+            continue;
         }
-    })
+        let width = lit_columns[x..].iter().take_while(|&&lit| lit).count();
+        let glyph = (top..=bottom)
+            .map(|y| {
+                (x..x + width)
+                    .map(|x| if banner[(x, y)] { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let letter = font
+            .iter()
+            .find(|(_, pattern)| trim_columns(pattern) == glyph)
+            .map(|&(letter, _)| letter)
+            .ok_or_else(|| {
+                AocError::Unsolvable(format!(
+                    "Unrecognized character {} at column {}:\n{}",
+                    letters.len() + 1,
+                    x + 1,
+                    glyph
+                ))
+            })?;
+        letters.push(letter);
+        x += width;
+    }
+
+    Ok(letters)
+}
+
+/// Removes the dark columns on both sides of a glyph pattern.
+fn trim_columns(pattern: &str) -> String {
+    let rows = pattern.lines().collect::<Vec<_>>();
+    let lit = |x: usize| rows.iter().any(|row| row.as_bytes().get(x) == Some(&b'#'));
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let left = (0..width).find(|&x| lit(x)).unwrap_or(0);
+    let right = (0..width).rev().find(|&x| lit(x)).map_or(0, |x| x + 1);
+
+    rows.iter()
+        .map(|row| row.get(left..right).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+pub fn test_recognize_text() {
+    let banner = "
+.##..###...##.
+#..#.#..#.#..#
+#..#.###..#...
+####.#..#.#...
+#..#.#..#.#..#
+#..#.###...##.";
+    assert_eq!(recognize_text(banner), Ok("ABC".to_string()));
+
+    let tall = "
+#....#..######
+#....#..#.....
+.#..#...#.....
+.#..#...#.....
+..##....#####.
+..##....#.....
+.#..#...#.....
+.#..#...#.....
+#....#..#.....
+#....#..#.....";
+    assert_eq!(recognize_text(tall), Ok("XF".to_string()));
+
+    assert_eq!(
+        recognize_text("#...\n#...\n#...\n#...\n#...\n####\n\n   #\n"),
+        Err(AocError::Unsolvable("No font is 8 pixels high".to_string()))
+    );
+    assert_eq!(
+        recognize_text("#..# #\n#..# #\n#### #\n#..# #\n#..# #\n#..# #"),
+        Err(AocError::Unsolvable(
+            "Unrecognized character 2 at column 6:\n#\n#\n#\n#\n#\n#".to_string()
+        ))
+    );
+    assert_eq!(
+        recognize_text("#x"),
+        Err(AocError::parse(1, 2, "x", "Invalid pixel"))
+    );
+}
+
+#[test]
+pub fn test_recognize() {
+    assert_eq!(
+        recognize(" ##  \n#  # \n#  # \n#### \n#  # \n#  # "),
+        Ok('A')
+    );
+    assert_eq!(recognize("#  #\n#  #\n#  #\n#  #\n#  #\n ## "), Ok('U'));
+    assert_eq!(
+        recognize(&format!("{}#### ####", "#    #   \n".repeat(5))),
+        Err(AocError::Unsolvable(
+            "Expected a single character, found LL".to_string()
+        ))
+    );
+}
+
+#[test]
+pub fn test_recognize_points() {
+    // an L and an I, drawn away from the origin
+    let mut points = (10..16).map(|y| (3, y)).collect::<Vec<_>>();
+    points.extend((4..7).map(|x| (x, 15)));
+    points.extend([(9, 10), (10, 10), (11, 10), (9, 15), (10, 15), (11, 15)]);
+    points.extend((11..15).map(|y| (10, y)));
+    assert_eq!(recognize_points(points), Ok("LI".to_string()));
+    assert_eq!(
+        recognize_points([]),
+        Err(AocError::Unsolvable("Empty banner".to_string()))
+    );
 }
@@ -0,0 +1,318 @@
+#![allow(dead_code)]
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Path is the outcome of a search: the states from the start to the goal,
+/// both included, and the cost of going through them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub states: Vec<S>,
+    pub cost: C,
+}
+
+/// Visited keeps each state reached so far with the one it was reached from,
+/// to rebuild the path once the goal is found.
+struct Visited<S> {
+    states: Vec<S>,
+    parents: Vec<Option<usize>>,
+    indices: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash> Visited<S> {
+    fn new(start: S) -> Self {
+        Self {
+            states: vec![start.clone()],
+            parents: vec![None],
+            indices: HashMap::from([(start, 0)]),
+        }
+    }
+
+    /// Returns the index of the state, if it was not visited yet.
+    fn insert(&mut self, state: S, parent: usize) -> Option<usize> {
+        match self.indices.entry(state) {
+            Entry::Occupied(_) => None,
+            Entry::Vacant(entry) => {
+                let index = self.states.len();
+                self.states.push(entry.key().clone());
+                self.parents.push(Some(parent));
+                entry.insert(index);
+                Some(index)
+            }
+        }
+    }
+
+    fn path(&self, mut index: usize) -> Vec<S> {
+        let mut path = vec![self.states[index].clone()];
+        while let Some(parent) = self.parents[index] {
+            path.push(self.states[parent].clone());
+            index = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Breadth-first search, finding the path to a goal with the fewest steps.
+/// The cost of the path is its number of steps.
+pub fn bfs<S, N, I, G>(start: S, mut neighbours: N, mut is_goal: G) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut visited = Visited::new(start);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(index) = queue.pop_front() {
+        if is_goal(&visited.states[index]) {
+            let states = visited.path(index);
+            let cost = states.len() - 1;
+            return Some(Path { states, cost });
+        }
+        for next in neighbours(&visited.states[index]) {
+            queue.extend(visited.insert(next, index));
+        }
+    }
+
+    None
+}
+
+/// Depth-first search, finding a path to a goal, not necessarily the
+/// shortest. The cost of the path is its number of steps.
+pub fn dfs<S, N, I, G>(start: S, mut neighbours: N, mut is_goal: G) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut visited = Visited::new(start);
+    let mut stack = vec![0];
+
+    while let Some(index) = stack.pop() {
+        if is_goal(&visited.states[index]) {
+            let states = visited.path(index);
+            let cost = states.len() - 1;
+            return Some(Path { states, cost });
+        }
+        for next in neighbours(&visited.states[index]) {
+            stack.extend(visited.insert(next, index));
+        }
+    }
+
+    None
+}
+
+/// Counts the distinct paths from the start to a goal, walking each of them
+/// depth-first. Goals are not walked through, and the states must not form a
+/// cycle, which usually means that they include what was visited so far.
+pub fn count_paths<S, N, I, G>(start: S, mut neighbours: N, mut is_goal: G) -> usize
+where
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut count = 0;
+    let mut stack = vec![start];
+
+    while let Some(state) = stack.pop() {
+        if is_goal(&state) {
+            count += 1;
+        } else {
+            stack.extend(neighbours(&state));
+        }
+    }
+
+    count
+}
+
+/// Dijkstra's algorithm, finding the cheapest path to a goal. The neighbours
+/// come with the cost of moving to them, which must not be negative.
+pub fn dijkstra<S, C, N, I, G>(start: S, neighbours: N, is_goal: G) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    astar(start, neighbours, |_| C::default(), is_goal)
+}
+
+/// A* search, finding the cheapest path to a goal. The heuristic estimates
+/// the cost from a state to the nearest goal, and must never overestimate it
+/// for the path to be the cheapest.
+pub fn astar<S, C, N, I, H, G>(
+    start: S,
+    mut neighbours: N,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut visited = Visited::new(start);
+    let mut costs = vec![C::default()];
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        // a cheaper way to this state was found after queueing it
+        if cost > costs[index] {
+            continue;
+        }
+        if is_goal(&visited.states[index]) {
+            let states = visited.path(index);
+            return Some(Path { states, cost });
+        }
+
+        for (next, step_cost) in neighbours(&visited.states[index]) {
+            let next_cost = cost + step_cost;
+            let next_index = match visited.indices.get(&next) {
+                Some(&next_index) if next_cost < costs[next_index] => {
+                    costs[next_index] = next_cost;
+                    visited.parents[next_index] = Some(index);
+                    next_index
+                }
+                Some(_) => continue,
+                None => {
+                    costs.push(next_cost);
+                    visited.insert(next, index).expect("state not visited yet")
+                }
+            };
+            let estimate = next_cost + heuristic(&visited.states[next_index]);
+            queue.push(Reverse((estimate, next_cost, next_index)));
+        }
+    }
+
+    None
+}
+
+/// Returns every state reachable from the start, the start included.
+pub fn flood_fill<S, N, I>(start: S, mut neighbours: N) -> HashSet<S>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut seen = HashSet::from([start.clone()]);
+    let mut stack = vec![start];
+
+    while let Some(state) = stack.pop() {
+        for next in neighbours(&state) {
+            if seen.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+
+    seen
+}
+
+/// Splits the states into groups reachable from one another, in the order of
+/// their first state. The neighbours must be symmetric, and should only
+/// return states among the given ones.
+pub fn connected_components<S, N, I>(
+    states: impl IntoIterator<Item = S>,
+    mut neighbours: N,
+) -> Vec<HashSet<S>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut components: Vec<HashSet<S>> = Vec::new();
+    for state in states {
+        if !components
+            .iter()
+            .any(|component| component.contains(&state))
+        {
+            components.push(flood_fill(state, &mut neighbours));
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+fn maze_neighbours<'a>(maze: &'a [&str]) -> impl Fn(&(usize, usize)) -> Vec<(usize, usize)> + 'a {
+    move |&(x, y)| {
+        [
+            (x + 1, y),
+            (x, y + 1),
+            (x.wrapping_sub(1), y),
+            (x, y.wrapping_sub(1)),
+        ]
+        .into_iter()
+        .filter(|&(x, y)| maze.get(y).and_then(|row| row.as_bytes().get(x)) == Some(&b'.'))
+        .collect()
+    }
+}
+
+#[test]
+pub fn test_unweighted() {
+    let maze = ["..#.", "#...", "..#."];
+    let neighbours = maze_neighbours(&maze);
+
+    let path = bfs((0, 0), &neighbours, |&p| p == (3, 2)).unwrap();
+    assert_eq!(path.cost, 5);
+    assert_eq!(
+        path.states,
+        [(0, 0), (1, 0), (1, 1), (2, 1), (3, 1), (3, 2)]
+    );
+
+    let path = dfs((0, 0), &neighbours, |&p| p == (3, 2)).unwrap();
+    assert_eq!(path.states.first(), Some(&(0, 0)));
+    assert_eq!(path.states.last(), Some(&(3, 2)));
+    assert_eq!(path.cost, path.states.len() - 1);
+
+    assert_eq!(bfs((0, 0), &neighbours, |&p| p == (2, 0)), None);
+    assert_eq!(flood_fill((3, 0), &neighbours).len(), 9);
+
+    let open = (0..4)
+        .flat_map(|x| (0..3).map(move |y| (x, y)))
+        .filter(|&(x, y)| maze[y].as_bytes()[x] == b'.');
+    let components = connected_components(open, &neighbours);
+    assert_eq!(components.len(), 1);
+}
+
+#[test]
+pub fn test_weighted() {
+    // going around costs 1 per step, while cutting through costs 10
+    let neighbours = |&n: &u32| match n {
+        0 => vec![(1, 1), (3, 10)],
+        1 => vec![(2, 1)],
+        2 => vec![(3, 1)],
+        _ => vec![],
+    };
+
+    let path = dijkstra(0, neighbours, |&n| n == 3).unwrap();
+    assert_eq!(
+        path,
+        Path {
+            states: vec![0, 1, 2, 3],
+            cost: 3
+        }
+    );
+
+    let path = astar(0, neighbours, |&n| 3 - n, |&n| n == 3).unwrap();
+    assert_eq!(path.cost, 3);
+
+    assert_eq!(dijkstra(1, neighbours, |&n| n == 0), None);
+    assert_eq!(
+        count_paths(
+            0,
+            |&n| neighbours(&n).into_iter().map(|(n, _)| n),
+            |&n| n == 3
+        ),
+        2
+    );
+}
@@ -0,0 +1,94 @@
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+/// AocError is the error returned while solving a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AocError {
+    /// Some text of the input could not be parsed
+    Parse {
+        /// 1-based line of the input
+        line: usize,
+        /// 1-based column of the line
+        column: usize,
+        /// The offending text
+        text: String,
+        message: String,
+    },
+    /// The input is malformed as a whole, e.g. a section is missing
+    InvalidInput(String),
+    /// The input is well formed but has no answer
+    Unsolvable(String),
+    /// The input was rejected before solving, e.g. because it is too large
+    Rejected(String),
+    /// The puzzle has no solution yet
+    NotImplemented,
+    /// No solver is registered for the year and day
+    NotAvailable { year: u16, day: u8 },
+    /// The year, day or part arguments are not valid
+    InvalidArgument(String),
+    /// Parsing or solving took longer than allowed
+    TimedOut(Duration),
+}
+
+impl AocError {
+    pub fn parse(line: usize, column: usize, text: &str, message: &str) -> Self {
+        Self::Parse {
+            line,
+            column,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Parse {
+                line,
+                column,
+                text,
+                message,
+            } => write!(f, "Line {}, column {}: {} {}", line, column, message, text),
+            Self::InvalidInput(message) | Self::Unsolvable(message) => write!(f, "{}", message),
+            Self::Rejected(message) | Self::InvalidArgument(message) => write!(f, "{}", message),
+            Self::NotImplemented => write!(f, "Not implemented"),
+            Self::NotAvailable { year, day } => {
+                write!(f, "Year {} day {} not available", year, day)
+            }
+            Self::TimedOut(timeout) => write!(f, "Timed out after {:?}", timeout),
+        }
+    }
+}
+
+impl Error for AocError {}
+
+/// Untyped errors raised while reading the input are considered invalid input.
+impl From<String> for AocError {
+    fn from(message: String) -> Self {
+        Self::InvalidInput(message)
+    }
+}
+
+impl From<&str> for AocError {
+    fn from(message: &str) -> Self {
+        Self::InvalidInput(message.to_string())
+    }
+}
+
+#[test]
+pub fn test_display() {
+    assert_eq!(
+        AocError::parse(3, 7, "abc", "Not a valid integer").to_string(),
+        "Line 3, column 7: Not a valid integer abc"
+    );
+    assert_eq!(
+        AocError::NotAvailable { year: 2015, day: 1 }.to_string(),
+        "Year 2015 day 1 not available"
+    );
+    assert_eq!(
+        AocError::from("Missing section"),
+        AocError::InvalidInput("Missing section".to_string())
+    );
+}
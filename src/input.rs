@@ -1,37 +1,126 @@
 #![allow(clippy::redundant_pub_crate)]
 #![allow(dead_code)]
 
-#[derive(Copy, Clone)]
+use std::str::FromStr;
+
+use crate::error::AocError;
+
+/// Part selects which parts of a puzzle to solve.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Part {
     One,
     Two,
+    /// Both parts, one after the other
+    Both,
+}
+
+/// SinglePart is one of the two parts of a puzzle, which are solved one at
+/// a time.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SinglePart {
+    One,
+    Two,
+}
+
+impl SinglePart {
+    pub const fn number(self) -> u8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for SinglePart {
+    type Error = AocError;
+
+    fn try_from(part: u8) -> Result<Self, Self::Error> {
+        match part {
+            1 => Ok(Self::One),
+            2 => Ok(Self::Two),
+            _ => Err(AocError::InvalidArgument(format!(
+                "Invalid part {}, expected 1 or 2",
+                part
+            ))),
+        }
+    }
+}
+
+impl Part {
+    /// Returns the single parts selected, in order.
+    pub const fn parts(self) -> &'static [SinglePart] {
+        match self {
+            Self::One => &[SinglePart::One],
+            Self::Two => &[SinglePart::Two],
+            Self::Both => &[SinglePart::One, SinglePart::Two],
+        }
+    }
+
+    /// Returns the number of the part, 0 standing for both.
+    pub const fn number(self) -> u8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+            Self::Both => 0,
+        }
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = AocError;
+
+    fn try_from(part: u8) -> Result<Self, Self::Error> {
+        match part {
+            1 => Ok(Self::One),
+            2 => Ok(Self::Two),
+            0 => Ok(Self::Both),
+            _ => Err(AocError::InvalidArgument(format!(
+                "Invalid part {}, expected 1, 2 or 0 for both",
+                part
+            ))),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "both" => Ok(Self::Both),
+            _ => s
+                .parse::<u8>()
+                .map_err(|_| AocError::InvalidArgument(format!("Invalid part {}", s)))?
+                .try_into(),
+        }
+    }
 }
 
 pub struct Input<'a> {
-    pub part: Part,
+    pub part: SinglePart,
     pub text: &'a str,
 }
 
 impl<'a> Input<'a> {
     pub const fn is_part_one(&self) -> bool {
-        matches!(self.part, Part::One)
+        matches!(self.part, SinglePart::One)
     }
 
     pub const fn is_part_two(&self) -> bool {
-        matches!(self.part, Part::Two)
+        matches!(self.part, SinglePart::Two)
     }
 
     pub fn part_values<T>(&self, if_part_one: T, if_part_two: T) -> T {
         match self.part {
-            Part::One => if_part_one,
-            Part::Two => if_part_two,
+            SinglePart::One => if_part_one,
+            SinglePart::Two => if_part_two,
         }
     }
 
     #[cfg(test)]
     pub const fn part_one(text: &'a str) -> Self {
         Self {
-            part: Part::One,
+            part: SinglePart::One,
             text,
         }
     }
@@ -39,7 +128,7 @@ impl<'a> Input<'a> {
     #[cfg(test)]
     pub const fn part_two(text: &'a str) -> Self {
         Self {
-            part: Part::Two,
+            part: SinglePart::Two,
             text,
         }
     }
@@ -48,7 +137,10 @@ impl<'a> Input<'a> {
 #[cfg(test)]
 macro_rules! test_part_one {
     ($input:tt => $expected:expr) => {
-        assert_eq!(solve(&mut Input::part_one($input)), Ok($expected));
+        assert_eq!(
+            <Day as $crate::solution::Solution>::solve(&mut $crate::input::Input::part_one($input)),
+            Ok($expected)
+        );
     };
 }
 
@@ -58,7 +150,10 @@ pub(crate) use test_part_one;
 #[cfg(test)]
 macro_rules! test_part_two {
     ($input:tt => $expected:expr) => {
-        assert_eq!(solve(&mut Input::part_two($input)), Ok($expected));
+        assert_eq!(
+            <Day as $crate::solution::Solution>::solve(&mut $crate::input::Input::part_two($input)),
+            Ok($expected)
+        );
     };
 }
 
@@ -68,7 +163,11 @@ pub(crate) use test_part_two;
 #[cfg(test)]
 macro_rules! test_part_one_error {
     ($input:tt => $expected:expr) => {
-        assert_eq!(Err($expected.into()), solve(&mut Input::part_one($input)));
+        assert_eq!(
+            Err($expected.to_string()),
+            <Day as $crate::solution::Solution>::solve(&mut $crate::input::Input::part_one($input))
+                .map_err(|error| error.to_string())
+        );
     };
 }
 #[cfg(test)]
@@ -77,8 +176,74 @@ pub(crate) use test_part_one_error;
 #[cfg(test)]
 macro_rules! test_part_two_error {
     ($input:tt => $expected:expr) => {
-        assert_eq!(Err($expected.into()), solve(&mut Input::part_two($input)));
+        assert_eq!(
+            Err($expected.to_string()),
+            <Day as $crate::solution::Solution>::solve(&mut $crate::input::Input::part_two($input))
+                .map_err(|error| error.to_string())
+        );
     };
 }
 #[cfg(test)]
 pub(crate) use test_part_two_error;
+
+/// Checks the answers to the input of a day against the expected ones of
+/// `answers.toml`. Every part must be listed there, parts marked as unknown
+/// are not checked.
+#[cfg(test)]
+macro_rules! test_expected_answers {
+    ($year:literal, $day:literal, $input:tt) => {
+        let answers = $crate::answers::Answers::expected();
+        for (part, mut input) in [
+            (1, $crate::input::Input::part_one($input)),
+            (2, $crate::input::Input::part_two($input)),
+        ] {
+            assert!(
+                answers.contains($year, $day, part),
+                "{} day {} part {} has no expected answer, list it as \"?\" if unknown",
+                $year,
+                $day,
+                part
+            );
+            if let Some(expected) = answers.get($year, $day, part) {
+                assert_eq!(
+                    <Day as $crate::solution::Solution>::solve(&mut input)
+                        .map(|answer| answer.to_string()),
+                    Ok(expected.to_string()),
+                    "{} day {} part {}",
+                    $year,
+                    $day,
+                    part
+                );
+            }
+        }
+    };
+}
+#[cfg(test)]
+pub(crate) use test_expected_answers;
+
+#[test]
+pub fn test_part() {
+    assert_eq!("1".parse::<Part>(), Ok(Part::One));
+    assert_eq!("2".parse::<Part>(), Ok(Part::Two));
+    assert_eq!("0".parse::<Part>(), Ok(Part::Both));
+    assert_eq!("both".parse::<Part>(), Ok(Part::Both));
+    assert_eq!(
+        "3".parse::<Part>(),
+        Err(AocError::InvalidArgument(
+            "Invalid part 3, expected 1, 2 or 0 for both".to_string()
+        ))
+    );
+    assert_eq!(
+        "one".parse::<Part>(),
+        Err(AocError::InvalidArgument("Invalid part one".to_string()))
+    );
+    assert_eq!(Part::Both.parts(), &[SinglePart::One, SinglePart::Two]);
+    assert_eq!(Part::Two.number(), 2);
+    assert_eq!(SinglePart::try_from(2), Ok(SinglePart::Two));
+    assert_eq!(
+        SinglePart::try_from(0),
+        Err(AocError::InvalidArgument(
+            "Invalid part 0, expected 1 or 2".to_string()
+        ))
+    );
+}
@@ -1,71 +1,155 @@
 #![forbid(unsafe_code)]
 #![crate_name = "advent_of_code"]
 
+mod answers;
 mod common;
-#[cfg_attr(test, macro_use)]
+mod error;
 mod input;
-mod year2021;
+mod options;
+mod registry;
+mod report;
+mod solution;
+pub mod year2021;
 
-use crate::input::{Input, Part};
+use std::any::Any;
+use std::sync::Arc;
 
-// Never inline to prevent stack size from blowing up in release builds.
-#[inline(never)]
-fn to_stringer_input<T: ToString>(
-    function: fn(&mut Input) -> Result<T, String>,
-    input: &mut Input,
-) -> Result<String, String> {
-    function(input).map(|value| value.to_string())
+use crate::registry::Solver;
+
+pub use crate::answers::Answers;
+pub use crate::error::AocError;
+pub use crate::input::{Part, SinglePart};
+pub use crate::options::SolveOptions;
+pub use crate::registry::available;
+pub use crate::report::Report;
+
+/// Parsed is the input of a puzzle once parsed, from which any of its parts
+/// can be solved.
+pub struct Parsed {
+    solver: &'static Solver,
+    value: Arc<dyn Any + Send + Sync>,
+    options: SolveOptions,
 }
 
-pub fn solve(day: u8, part: u8, input: &str) -> Result<String, String> {
-    if input.is_empty() {
-        return Err("No input provided".to_string());
-    } else if input.len() > 200_000 {
-        return Err("Input too large".to_string());
-    } else if !input.is_ascii() {
-        return Err("Non-ASCII input provided".to_string());
-    } else if !matches!(day, 1..=25) {
-        return Err("Invalid day provided".to_string());
+impl Parsed {
+    /// Solves part 1 or 2 of the puzzle, within the timeout of the options it
+    /// was parsed with. Both parts are solved by [solve_parts](Self::solve_parts).
+    pub fn solve(&self, part: u8) -> Result<String, AocError> {
+        self.solve_single(SinglePart::try_from(part)?)
     }
 
-    let mut input = Input {
-        part: if part == 1 { Part::One } else { Part::Two },
-        text: input,
-    };
+    /// Solves the selected parts of the puzzle, returning their answers in order.
+    pub fn solve_parts(&self, part: Part) -> Result<Vec<String>, AocError> {
+        part.parts()
+            .iter()
+            .map(|&part| self.solve_single(part))
+            .collect()
+    }
 
-    match day {
-        1 => to_stringer_input(year2021::day01::solve, &mut input),
-        2 => to_stringer_input(year2021::day02::solve, &mut input),
-        3 => to_stringer_input(year2021::day03::solve, &mut input),
-        4 => to_stringer_input(year2021::day04::solve, &mut input),
-        5 => to_stringer_input(year2021::day05::solve, &mut input),
-        6 => to_stringer_input(year2021::day06::solve, &mut input),
-        7 => to_stringer_input(year2021::day07::solve, &mut input),
-        8 => to_stringer_input(year2021::day08::solve, &mut input),
-        9 => to_stringer_input(year2021::day09::solve, &mut input),
-        10 => to_stringer_input(year2021::day10::solve, &mut input),
-        11 => to_stringer_input(year2021::day11::solve, &mut input),
-        12 => to_stringer_input(year2021::day12::solve, &mut input),
-        13 => to_stringer_input(year2021::day13::solve, &mut input),
-        14 => to_stringer_input(year2021::day14::solve, &mut input),
-        15 => to_stringer_input(year2021::day15::solve, &mut input),
-        16 => to_stringer_input(year2021::day16::solve, &mut input),
-        17 => to_stringer_input(year2021::day17::solve, &mut input),
-        18 => to_stringer_input(year2021::day18::solve, &mut input),
-        19 => to_stringer_input(year2021::day19::solve, &mut input),
-        20 => to_stringer_input(year2021::day20::solve, &mut input),
-        21 => to_stringer_input(year2021::day21::solve, &mut input),
-        22 => to_stringer_input(year2021::day22::solve, &mut input),
-        23 => to_stringer_input(year2021::day23::solve, &mut input),
-        24 => to_stringer_input(year2021::day24::solve, &mut input),
-        25 => to_stringer_input(year2021::day25::solve, &mut input),
-        _ => Err(format!("Unsupported day {}", day)),
+    fn solve_single(&self, part: SinglePart) -> Result<String, AocError> {
+        let solve = self.solver.solve;
+        let value = Arc::clone(&self.value);
+        self.options.run(move || solve(value.as_ref(), part))
     }
 }
 
+/// Checks the input against the default [SolveOptions] and parses it for the
+/// puzzle of the given year and day.
+pub fn parse(year: u16, day: u8, input: &str) -> Result<Parsed, AocError> {
+    parse_with(year, day, input, &SolveOptions::default())
+}
+
+/// Checks the input against the options and parses it for the puzzle of the
+/// given year and day.
+pub fn parse_with(
+    year: u16,
+    day: u8,
+    input: &str,
+    options: &SolveOptions,
+) -> Result<Parsed, AocError> {
+    options.check(input)?;
+
+    let solver = registry::find(year, day)?;
+    let parse = solver.parse;
+    let value = match options.timeout {
+        Some(_) => {
+            let input = input.to_string();
+            options.run(move || parse(&input))?
+        }
+        None => parse(input)?,
+    };
+    Ok(Parsed {
+        solver,
+        value,
+        options: options.clone(),
+    })
+}
+
+pub fn solve(year: u16, day: u8, part: u8, input: &str) -> Result<String, AocError> {
+    parse(year, day, input)?.solve(part)
+}
+
+/// Parses the input once and solves both parts of the puzzle.
+pub fn solve_both(year: u16, day: u8, input: &str) -> Result<(String, String), AocError> {
+    let parsed = parse(year, day, input)?;
+    Ok((parsed.solve(1)?, parsed.solve(2)?))
+}
+
+/// Parses the year, day and part arguments, the part being `1`, `2`, or `0`
+/// or `both` for both parts.
+pub fn parse_arguments(year: &str, day: &str, part: &str) -> Result<(u16, u8, Part), AocError> {
+    let invalid = |argument: &str| AocError::InvalidArgument(format!("Invalid {}", argument));
+    let year = year.parse::<u16>().map_err(|_| invalid("year"))?;
+    let day = day.parse::<u8>().map_err(|_| invalid("day"))?;
+    let part = part.parse::<Part>()?;
+    Ok((year, day, part))
+}
+
 /// A version of [solve](fn.solve.html) that takes strings as arguments and parses them to the required types.
-pub fn solve_raw(day: &str, part: &str, input: &str) -> Result<String, String> {
-    let day = day.parse::<u8>().map_err(|_| "Invalid day")?;
-    let part = part.parse::<u8>().map_err(|_| "Invalid part")?;
-    solve(day, part, input)
+/// When both parts are selected, their answers are returned on separate lines.
+pub fn solve_raw(year: &str, day: &str, part: &str, input: &str) -> Result<String, AocError> {
+    let (year, day, part) = parse_arguments(year, day, part)?;
+    Ok(parse(year, day, input)?.solve_parts(part)?.join("\n"))
+}
+
+#[test]
+pub fn test_solve_both() {
+    let input = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+    assert_eq!(
+        solve_both(2021, 1, input),
+        Ok(("7".to_string(), "5".to_string()))
+    );
+    assert_eq!(
+        solve_both(2021, 25, ">v\nv>"),
+        Err(AocError::NotImplemented)
+    );
+
+    let parsed = parse(2021, 1, input).map_err(|error| error.to_string());
+    assert_eq!(
+        parsed.and_then(|parsed| parsed.solve(2).map_err(|error| error.to_string())),
+        Ok("5".to_string())
+    );
+}
+
+#[test]
+pub fn test_solve_part() {
+    let input = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+    assert_eq!(
+        solve(2021, 1, 7, input),
+        Err(AocError::InvalidArgument(
+            "Invalid part 7, expected 1 or 2".to_string()
+        ))
+    );
+    assert_eq!(
+        solve(2021, 1, 0, input),
+        Err(AocError::InvalidArgument(
+            "Invalid part 0, expected 1 or 2".to_string()
+        ))
+    );
+    assert_eq!(
+        solve_raw("2021", "1", "both", input),
+        Ok("7\n5".to_string())
+    );
+    assert_eq!(solve_raw("2021", "1", "0", input), Ok("7\n5".to_string()));
+    assert_eq!(solve_raw("2021", "1", "1", input), Ok("7".to_string()));
 }
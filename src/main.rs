@@ -1,39 +1,404 @@
 #![forbid(unsafe_code)]
 use std::env;
-use std::io::Read;
+use std::fmt;
+use std::fs::read_to_string;
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
-use advent_of_code::solve_raw;
+use advent_of_code::{
+    available, parse_arguments, parse_with, Answers, AocError, Part, Report, SolveOptions,
+};
+
+const DEFAULT_YEAR: &str = "2021";
+const DEFAULT_INPUT_DIR: &str = "src";
+const DEFAULT_ANSWERS: &str = "answers.toml";
+
+/// Exit code of `run-all` and `verify` when an answer is wrong or a puzzle failed.
+const CHECK_FAILED: i32 = 9;
+
+/// Returns the process exit code of an error, distinct for each kind of error.
+/// Exit code 1 is left for usage and I/O errors.
+fn exit_code(error: &AocError) -> i32 {
+    match error {
+        AocError::InvalidArgument(_) => 2,
+        AocError::NotAvailable { .. } => 3,
+        AocError::Rejected(_) => 4,
+        AocError::Parse { .. } => 5,
+        AocError::InvalidInput(_) => 6,
+        AocError::Unsolvable(_) => 7,
+        AocError::NotImplemented => 8,
+        AocError::TimedOut(_) => 10,
+    }
+}
+
+/// Check is the outcome of comparing an answer to the expected one.
+enum Check {
+    Correct,
+    Wrong { expected: String },
+    Unchecked,
+    Skipped,
+    Failed(AocError),
+}
+
+impl Check {
+    fn new(answer: &Result<String, AocError>, expected: Option<&str>) -> Self {
+        match (answer, expected) {
+            (Err(AocError::NotImplemented), _) => Self::Skipped,
+            (Err(error), _) => Self::Failed(error.clone()),
+            (Ok(answer), Some(expected)) if answer == expected => Self::Correct,
+            (Ok(_), Some(expected)) => Self::Wrong {
+                expected: expected.to_string(),
+            },
+            (Ok(_), None) => Self::Unchecked,
+        }
+    }
+
+    const fn is_failure(&self) -> bool {
+        matches!(self, Self::Wrong { .. } | Self::Failed(_))
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "ok"),
+            Self::Wrong { expected } => write!(f, "MISMATCH, expected {}", expected),
+            Self::Unchecked => write!(f, "unchecked"),
+            Self::Skipped => write!(f, "skipped"),
+            Self::Failed(error) => write!(f, "failed: {}", error),
+        }
+    }
+}
+
+fn read_input() -> Result<String, String> {
+    let mut input = String::new();
+
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| format!("Error reading input: {}", e))?;
+
+    Ok(input)
+}
+
+fn read_answers(answers_path: &str) -> Result<Answers, String> {
+    read_to_string(answers_path)
+        .map_err(|e| format!("Error reading {}: {}", answers_path, e))?
+        .parse::<Answers>()
+        .map_err(|e| format!("Error in {}: {}", answers_path, e))
+}
+
+fn print_row(puzzle: &str, answer: &str, parse_time: &str, solve_time: &str, check: &str) {
+    println!(
+        "{:<20} {:>16} {:>10} {:>10}  {}",
+        puzzle, answer, parse_time, solve_time, check
+    );
+}
+
+/// Format is how results are printed to stdout.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    /// One JSON object per line and solved part, see [Report::to_json]
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Invalid format {}", s)),
+        }
+    }
+}
+
+/// Solves both parts of every available puzzle, reading the input of each day
+/// from `<input_dir>/yearXXXX/dayNN_input.txt`, and prints a table comparing
+/// the answers to the expected ones. Returns whether no answer was wrong and
+/// no puzzle failed.
+fn run_all(
+    input_dir: &str,
+    answers_path: &str,
+    format: Format,
+    options: &SolveOptions,
+) -> Result<bool, String> {
+    let answers = read_answers(answers_path)?;
+    let elapsed = |duration: Duration| format!("{:.1?}", duration);
+
+    if format == Format::Text {
+        print_row("Puzzle", "Answer", "Parse", "Solve", "Check");
+    }
+    let mut success = true;
+    for (year, day) in available() {
+        let input_path = Path::new(input_dir)
+            .join(format!("year{}", year))
+            .join(format!("day{:02}_input.txt", day));
+        // a missing input skips the day, an unreadable one fails it
+        let input = match read_to_string(&input_path) {
+            Ok(input) => Ok(input),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(String::new()),
+            Err(error) => Err(AocError::Rejected(format!(
+                "Cannot read {}: {}",
+                input_path.display(),
+                error
+            ))),
+        };
+
+        let started = Instant::now();
+        let parsed = match &input {
+            Ok(input) => parse_with(year, day, input, options),
+            Err(error) => Err(error.clone()),
+        };
+        let parse_time = started.elapsed();
+
+        for part in 1..=2 {
+            let puzzle = format!("{} day {:02} part {}", year, day, part);
+            if matches!(input.as_deref(), Ok("")) {
+                match format {
+                    Format::Text => print_row(&puzzle, "-", "-", "-", "skipped, no input"),
+                    Format::Json => {
+                        let report = Report {
+                            year,
+                            day,
+                            part,
+                            answer: None,
+                            parse_time: Duration::ZERO,
+                            solve_time: Duration::ZERO,
+                        };
+                        println!("{}", report.to_json());
+                    }
+                }
+                continue;
+            }
+
+            let started = Instant::now();
+            let answer = match &parsed {
+                Ok(parsed) => parsed.solve(part),
+                Err(error) => Err(error.clone()),
+            };
+            let solve_time = started.elapsed();
+
+            let check = Check::new(&answer, answers.get(year, day, part));
+            success &= !check.is_failure();
+
+            match format {
+                Format::Text => print_row(
+                    &puzzle,
+                    answer.as_deref().unwrap_or("-"),
+                    &elapsed(parse_time),
+                    &elapsed(solve_time),
+                    &check.to_string(),
+                ),
+                Format::Json => {
+                    let report = Report {
+                        year,
+                        day,
+                        part,
+                        answer: Some(answer),
+                        parse_time,
+                        solve_time,
+                    };
+                    println!("{}", report.to_json());
+                }
+            }
+        }
+    }
+
+    Ok(success)
+}
+
+/// Solves both parts of a puzzle and compares the answers to the expected
+/// ones. Returns whether no answer was wrong and no part failed.
+fn verify(
+    year: u16,
+    day: u8,
+    input: &str,
+    answers_path: &str,
+    format: Format,
+    options: &SolveOptions,
+) -> Result<bool, String> {
+    let answers = read_answers(answers_path)?;
+    let started = Instant::now();
+    let parsed = parse_with(year, day, input, options);
+    let parse_time = started.elapsed();
+
+    let mut success = true;
+    for part in 1..=2 {
+        let started = Instant::now();
+        let answer = match &parsed {
+            Ok(parsed) => parsed.solve(part),
+            Err(error) => Err(error.clone()),
+        };
+        let solve_time = started.elapsed();
+
+        let check = Check::new(&answer, answers.get(year, day, part));
+        success &= !check.is_failure();
+        match (format, answer) {
+            (Format::Text, Ok(answer)) => println!("Part {}: {} ({})", part, answer, check),
+            (Format::Text, Err(_)) => println!("Part {}: {}", part, check),
+            (Format::Json, answer) => {
+                let report = Report {
+                    year,
+                    day,
+                    part,
+                    answer: Some(answer),
+                    parse_time,
+                    solve_time,
+                };
+                println!("{}", report.to_json());
+            }
+        }
+    }
+
+    Ok(success)
+}
+
+/// Exits with the error printed to stderr when the arguments are not valid.
+fn valid_arguments(year: &str, day: &str, part: &str) -> (u16, u8, Part) {
+    parse_arguments(year, day, part).unwrap_or_else(|error| {
+        eprintln!("Error: {}", error);
+        std::process::exit(exit_code(&error));
+    })
+}
 
 fn main() -> Result<(), String> {
+    let defaults = SolveOptions::default();
     let usage = || -> ! {
-        eprintln!("Arguments: <day> <part>");
-        eprintln!("    where: day is 1-25");
-        eprintln!("       and part is 1 or 2");
+        eprintln!("Arguments: [<options>] [<year>] <day> <part>");
+        eprintln!("    where: year defaults to {}", DEFAULT_YEAR);
+        eprintln!("       and day is 1-25");
+        eprintln!("       and part is 1, 2, or both (or 0)");
+        eprintln!("       or: [<options>] verify [<year>] <day>");
+        eprintln!("   checks the answers to the input read from stdin");
+        eprintln!("       or: [<options>] run-all [<input dir>] [<answers file>]");
+        eprintln!("    where: input dir defaults to {}", DEFAULT_INPUT_DIR);
+        eprintln!("       and answers file defaults to {}", DEFAULT_ANSWERS);
+        eprintln!("Options:");
+        eprintln!("  --time               reports the parse and solve times to stderr");
+        eprintln!("  --format text|json   prints text (default) or one JSON object per part");
+        eprintln!(
+            "  --max-size <bytes>   rejects larger inputs (default {})",
+            defaults.max_size
+        );
+        eprintln!("  --allow-non-ascii    accepts inputs with non-ASCII characters");
+        eprintln!("  --timeout <seconds>  gives up parsing or solving a part after that long");
         std::process::exit(1);
     };
 
-    let args: Vec<String> = env::args().collect();
+    let mut time = false;
+    let mut format = Format::Text;
+    let mut options = SolveOptions::default();
+    let mut args = Vec::new();
+    let mut all_args = env::args().skip(1);
+    while let Some(arg) = all_args.next() {
+        match arg.as_str() {
+            "-v" | "--version" => {
+                println!("{}", env!("CARGO_PKG_VERSION"));
+                return Ok(());
+            }
+            "-t" | "--time" => time = true,
+            "--format" => {
+                format = all_args
+                    .next()
+                    .and_then(|format| format.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            "--max-size" => {
+                options.max_size = all_args
+                    .next()
+                    .and_then(|size| size.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            "--allow-non-ascii" => options.ascii_only = false,
+            "--timeout" => {
+                let timeout = all_args
+                    .next()
+                    .and_then(|seconds| Duration::try_from_secs_f64(seconds.parse().ok()?).ok())
+                    .unwrap_or_else(|| usage());
+                options.timeout = Some(timeout);
+            }
+            _ => args.push(arg),
+        }
+    }
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    if let ["run-all", rest @ ..] = &args[..] {
+        let input_dir = rest.first().copied().unwrap_or(DEFAULT_INPUT_DIR);
+        let answers_path = rest.get(1).copied().unwrap_or(DEFAULT_ANSWERS);
+        if rest.len() > 2 {
+            usage();
+        }
+        if !run_all(input_dir, answers_path, format, &options)? {
+            std::process::exit(CHECK_FAILED);
+        }
+        return Ok(());
+    }
 
-    if args.iter().any(|s| s == "-v" || s == "--version") {
-        println!("{}", env!("CARGO_PKG_VERSION"));
+    if let ["verify", rest @ ..] = &args[..] {
+        let (year, day) = match rest {
+            [year, day] => (*year, *day),
+            [day] => (DEFAULT_YEAR, *day),
+            _ => usage(),
+        };
+        let (year, day, _) = valid_arguments(year, day, "1");
+        if !verify(year, day, &read_input()?, DEFAULT_ANSWERS, format, &options)? {
+            std::process::exit(CHECK_FAILED);
+        }
         return Ok(());
     }
 
-    if args.len() == 3 {
-        let day = &args[1];
-        let part = &args[2];
-        let mut input = String::new();
+    let (year, day, part) = match args[..] {
+        [year, day, part] => (year, day, part),
+        [day, part] => (DEFAULT_YEAR, day, part),
+        _ => usage(),
+    };
+    let (year, day, part) = valid_arguments(year, day, part);
 
-        std::io::stdin()
-            .read_to_string(&mut input)
-            .map_err(|e| format!("Error reading input: {}", e.to_string()))?;
+    let input = read_input()?;
 
-        let solution =
-            solve_raw(day, part, input.as_ref()).unwrap_or_else(|e| format!("Error: {}", e));
-        println!("{}", solution);
-    } else {
-        usage();
+    let started = Instant::now();
+    let parsed = parse_with(year, day, input.as_ref(), &options);
+    let parse_time = started.elapsed();
+
+    let mut failure = None;
+    for &part in part.parts() {
+        let started = Instant::now();
+        let answer = match &parsed {
+            Ok(parsed) => parsed.solve(part.number()),
+            Err(error) => Err(error.clone()),
+        };
+        let solve_time = started.elapsed();
+        if time {
+            eprintln!("Parsed in {:?}, solved in {:?}", parse_time, solve_time);
+        }
+
+        match (format, &answer) {
+            (Format::Text, Ok(answer)) => println!("{}", answer),
+            (Format::Text, Err(error)) => {
+                eprintln!("Error: {}", error);
+                std::process::exit(exit_code(error));
+            }
+            (Format::Json, _) => {
+                let report = Report {
+                    year,
+                    day,
+                    part: part.number(),
+                    answer: Some(answer.clone()),
+                    parse_time,
+                    solve_time,
+                };
+                println!("{}", report.to_json());
+            }
+        }
+        failure = failure.or(answer.err());
     }
 
-    Ok(())
+    match failure {
+        Some(error) => std::process::exit(exit_code(&error)),
+        None => Ok(()),
+    }
 }
@@ -0,0 +1,112 @@
+use std::panic;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use crate::error::AocError;
+
+/// SolveOptions are the limits applied to the input and to the time spent
+/// parsing and solving it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveOptions {
+    /// Inputs longer than this many bytes are rejected
+    pub max_size: usize,
+    /// Whether inputs with non-ASCII characters are rejected
+    pub ascii_only: bool,
+    /// How long parsing or solving a part may take, if limited
+    pub timeout: Option<Duration>,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        Self {
+            max_size: 200_000,
+            ascii_only: true,
+            timeout: None,
+        }
+    }
+}
+
+impl SolveOptions {
+    /// Rejects the input if it is empty or breaks the size or ASCII limits.
+    pub fn check(&self, input: &str) -> Result<(), AocError> {
+        if input.is_empty() {
+            Err(AocError::Rejected("No input provided".to_string()))
+        } else if input.len() > self.max_size {
+            Err(AocError::Rejected("Input too large".to_string()))
+        } else if self.ascii_only && !input.is_ascii() {
+            Err(AocError::Rejected("Non-ASCII input provided".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Runs the function, giving up after the timeout if any. A thread cannot
+    /// be stopped, so on timeout the function keeps running in the background
+    /// until it returns and its result is dropped.
+    pub(crate) fn run<T, F>(&self, f: F) -> Result<T, AocError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T, AocError> + Send + 'static,
+    {
+        let timeout = match self.timeout {
+            Some(timeout) => timeout,
+            None => return f(),
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let worker = thread::spawn(move || sender.send(f()));
+        match receiver.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(AocError::TimedOut(timeout)),
+            // the sender is only dropped without sending if the function panicked
+            Err(mpsc::RecvTimeoutError::Disconnected) => match worker.join() {
+                Err(panic) => panic::resume_unwind(panic),
+                Ok(_) => unreachable!("worker returned without sending"),
+            },
+        }
+    }
+}
+
+#[test]
+pub fn test_check() {
+    let options = SolveOptions {
+        max_size: 4,
+        ..SolveOptions::default()
+    };
+    assert_eq!(options.check("1234"), Ok(()));
+    assert_eq!(
+        options.check("12345"),
+        Err(AocError::Rejected("Input too large".to_string()))
+    );
+    assert_eq!(
+        options.check(""),
+        Err(AocError::Rejected("No input provided".to_string()))
+    );
+    assert_eq!(
+        options.check("é"),
+        Err(AocError::Rejected("Non-ASCII input provided".to_string()))
+    );
+
+    let options = SolveOptions {
+        ascii_only: false,
+        ..options
+    };
+    assert_eq!(options.check("é"), Ok(()));
+}
+
+#[test]
+pub fn test_run() {
+    let options = SolveOptions {
+        timeout: Some(Duration::from_millis(10)),
+        ..SolveOptions::default()
+    };
+    assert_eq!(options.run(|| Ok(1)), Ok(1));
+    assert_eq!(
+        options.run(|| {
+            thread::sleep(Duration::from_secs(1));
+            Ok(1)
+        }),
+        Err(AocError::TimedOut(Duration::from_millis(10)))
+    );
+}
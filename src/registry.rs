@@ -0,0 +1,95 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::error::AocError;
+use crate::input::SinglePart;
+use crate::solution::Solution;
+use crate::year2021;
+
+/// Solver is the entry point of a single puzzle. The parsed input is type
+/// erased so that solvers of different days fit in a single list, and the
+/// answers are converted to strings.
+pub struct Solver {
+    pub day: u8,
+    pub parse: fn(&str) -> Result<Arc<dyn Any + Send + Sync>, AocError>,
+    pub solve: fn(&dyn Any, SinglePart) -> Result<String, AocError>,
+}
+
+impl Solver {
+    pub const fn of<S: Solution>(day: u8) -> Self {
+        Self {
+            day,
+            parse: parse_any::<S>,
+            solve: solve_any::<S>,
+        }
+    }
+}
+
+// Never inline to prevent stack size from blowing up in release builds.
+#[inline(never)]
+fn parse_any<S: Solution>(text: &str) -> Result<Arc<dyn Any + Send + Sync>, AocError> {
+    Ok(Arc::new(S::parse(text)?))
+}
+
+#[inline(never)]
+fn solve_any<S: Solution>(parsed: &dyn Any, part: SinglePart) -> Result<String, AocError> {
+    let parsed = parsed
+        .downcast_ref::<S::Parsed>()
+        .expect("parsed input of another solver");
+    let answer = match part {
+        SinglePart::One => S::part_one(parsed)?,
+        SinglePart::Two => S::part_two(parsed)?,
+    };
+    Ok(answer.to_string())
+}
+
+/// Builds the list of solvers of a year from `day => module` pairs, where
+/// each module exposes a `Day` type implementing [Solution].
+macro_rules! solvers {
+    ($($day:literal => $module:ident),* $(,)?) => {
+        pub const SOLVERS: &[$crate::registry::Solver] = &[
+            $($crate::registry::Solver::of::<$module::Day>($day)),*
+        ];
+    };
+}
+pub(crate) use solvers;
+
+/// The solvers of every year. Adding a year only takes its module and a line here.
+const YEARS: &[(u16, &[Solver])] = &[(2021, year2021::SOLVERS)];
+
+/// Returns the solver registered for the given year and day.
+pub fn find(year: u16, day: u8) -> Result<&'static Solver, AocError> {
+    YEARS
+        .iter()
+        .filter(|(y, _)| *y == year)
+        .flat_map(|(_, solvers)| solvers.iter())
+        .find(|solver| solver.day == day)
+        .ok_or(AocError::NotAvailable { year, day })
+}
+
+/// Returns every registered (year, day) pair, in order.
+pub fn available() -> Vec<(u16, u8)> {
+    let mut days = YEARS
+        .iter()
+        .flat_map(|(year, solvers)| solvers.iter().map(move |solver| (*year, solver.day)))
+        .collect::<Vec<_>>();
+    days.sort_unstable();
+    days
+}
+
+#[test]
+pub fn test_find() {
+    assert_eq!(find(2021, 25).map(|solver| solver.day), Ok(25));
+    assert_eq!(
+        find(2021, 26).map(|solver| solver.day),
+        Err(AocError::NotAvailable {
+            year: 2021,
+            day: 26
+        })
+    );
+    assert_eq!(
+        find(2015, 1).map(|solver| solver.day),
+        Err(AocError::NotAvailable { year: 2015, day: 1 })
+    );
+    assert_eq!(available().len(), 25);
+}
@@ -0,0 +1,104 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::error::AocError;
+
+/// Report is the outcome of solving one part of a puzzle, as printed by the
+/// `--format json` option of the binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    /// The answer, or `None` when the part was skipped for lack of input
+    pub answer: Option<Result<String, AocError>>,
+    /// Time spent parsing the input, shared by both parts of a day
+    pub parse_time: Duration,
+    /// Time spent solving the part alone
+    pub solve_time: Duration,
+}
+
+impl Report {
+    /// Returns the report as a single line JSON object with the fields `year`,
+    /// `day`, `part`, `answer`, `error` and `elapsed_ns`, followed by the
+    /// `parse_ns` and `solve_ns` that `elapsed_ns` adds up. Either `answer` or
+    /// `error` is `null`, the error being `skipped` for a skipped part. Adding
+    /// up the times of both parts counts the parsing twice.
+    pub fn to_json(&self) -> String {
+        let (answer, error) = match &self.answer {
+            Some(Ok(answer)) => (json_string(answer), "null".to_string()),
+            Some(Err(error)) => ("null".to_string(), json_string(&error.to_string())),
+            None => ("null".to_string(), json_string("skipped")),
+        };
+        format!(
+            r#"{{"year":{},"day":{},"part":{},"answer":{},"error":{},"elapsed_ns":{},"parse_ns":{},"solve_ns":{}}}"#,
+            self.year,
+            self.day,
+            self.part,
+            answer,
+            error,
+            (self.parse_time + self.solve_time).as_nanos(),
+            self.parse_time.as_nanos(),
+            self.solve_time.as_nanos()
+        )
+    }
+}
+
+/// Quotes the text as a JSON string, escaping the characters JSON requires.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[test]
+pub fn test_to_json() {
+    let report = Report {
+        year: 2021,
+        day: 13,
+        part: 2,
+        answer: Some(Ok("HGAJBEHC".to_string())),
+        parse_time: Duration::from_micros(1480),
+        solve_time: Duration::from_micros(20),
+    };
+    assert_eq!(
+        report.to_json(),
+        r#"{"year":2021,"day":13,"part":2,"answer":"HGAJBEHC","error":null,"elapsed_ns":1500000,"parse_ns":1480000,"solve_ns":20000}"#
+    );
+
+    let report = Report {
+        answer: Some(Err(AocError::parse(3, 1, "a\t\"b\"", "Invalid line"))),
+        parse_time: Duration::ZERO,
+        solve_time: Duration::from_nanos(42),
+        ..report
+    };
+    assert_eq!(
+        report.to_json(),
+        r#"{"year":2021,"day":13,"part":2,"answer":null,"error":"Line 3, column 1: Invalid line a\t\"b\"","elapsed_ns":42,"parse_ns":0,"solve_ns":42}"#
+    );
+
+    let report = Report {
+        answer: None,
+        parse_time: Duration::ZERO,
+        solve_time: Duration::ZERO,
+        ..report
+    };
+    assert_eq!(
+        report.to_json(),
+        r#"{"year":2021,"day":13,"part":2,"answer":null,"error":"skipped","elapsed_ns":0,"parse_ns":0,"solve_ns":0}"#
+    );
+
+    assert_eq!(json_string("\\\n\u{1}"), r#""\\\n\u0001""#);
+}
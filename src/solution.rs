@@ -0,0 +1,30 @@
+use crate::error::AocError;
+#[cfg(test)]
+use crate::input::Input;
+
+/// Solution is a puzzle whose input is parsed once and then used to answer
+/// both of its parts.
+pub trait Solution {
+    /// The input of the puzzle once parsed, shared with the thread solving it
+    /// when a timeout is set
+    type Parsed: Send + Sync + 'static;
+    /// The answer of each part
+    type Answer: ToString;
+
+    fn parse(text: &str) -> Result<Self::Parsed, AocError>;
+
+    fn part_one(parsed: &Self::Parsed) -> Result<Self::Answer, AocError>;
+
+    fn part_two(parsed: &Self::Parsed) -> Result<Self::Answer, AocError>;
+
+    /// Parses the input and answers the part it asks for.
+    #[cfg(test)]
+    fn solve(input: &mut Input) -> Result<Self::Answer, AocError> {
+        let parsed = Self::parse(input.text)?;
+        if input.is_part_one() {
+            Self::part_one(&parsed)
+        } else {
+            Self::part_two(&parsed)
+        }
+    }
+}
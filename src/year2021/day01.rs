@@ -1,17 +1,27 @@
 use crate::common::parse_lines;
-use crate::input::Input;
+use crate::error::AocError;
+use crate::solution::Solution;
 
-pub fn solve(input: &mut Input) -> Result<u32, String> {
-    let measurement = parse_lines::<u32>(input.text)?;
+pub struct Day;
 
-    if input.is_part_one() {
+impl Solution for Day {
+    type Parsed = Vec<u32>;
+    type Answer = u32;
+
+    fn parse(text: &str) -> Result<Vec<u32>, AocError> {
+        parse_lines::<u32>(text)
+    }
+
+    fn part_one(measurement: &Vec<u32>) -> Result<u32, AocError> {
         solve_part_one(measurement)
-    } else {
+    }
+
+    fn part_two(measurement: &Vec<u32>) -> Result<u32, AocError> {
         solve_part_two(measurement)
     }
 }
 
-pub fn solve_part_one(measurement: Vec<u32>) -> Result<u32, String> {
+pub fn solve_part_one(measurement: &[u32]) -> Result<u32, AocError> {
     Ok(measurement
         .windows(2)
         .filter(|data| data.last() > data.first())
@@ -20,7 +30,7 @@ pub fn solve_part_one(measurement: Vec<u32>) -> Result<u32, String> {
         .unwrap())
 }
 
-pub fn solve_part_two(measurement: Vec<u32>) -> Result<u32, String> {
+pub fn solve_part_two(measurement: &[u32]) -> Result<u32, AocError> {
     Ok(measurement
         .windows(3)
         .map(|window| -> u32 { window.iter().sum() })
@@ -34,9 +44,8 @@ pub fn solve_part_two(measurement: Vec<u32>) -> Result<u32, String> {
 
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::test_expected_answers;
 
     let file_input = include_str!("day01_input.txt");
-    test_part_one!(file_input => 1681);
-    test_part_two!(file_input => 1704);
+    test_expected_answers!(2021, 1, file_input);
 }
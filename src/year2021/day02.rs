@@ -1,6 +1,9 @@
-use crate::input::Input;
+use crate::common::parse_lines::{lines, Span};
+use crate::error::AocError;
+use crate::solution::Solution;
 
-enum SubmarineCommand {
+#[derive(Clone, Copy)]
+pub enum SubmarineCommand {
     Forward(u32),
     Up(u32),
     Down(u32),
@@ -38,65 +41,54 @@ impl Submarine {
 }
 
 impl SubmarineCommand {
-    fn translate(raw_command: &str) -> Result<Self, String> {
-        let raw_command: Vec<&str> = raw_command.trim().split(' ').collect();
-        let direction = match raw_command.first() {
-            Some(direction) => direction,
-            None => return Err("Cannot extract a direction".to_string()),
+    /// Parses a command such as `forward 5`.
+    fn parse(line: Span) -> Result<Self, AocError> {
+        let (direction, steps) = match line.words().collect::<Vec<_>>()[..] {
+            [direction, steps] => (direction, steps.parse::<u32>()?),
+            _ => return Err(line.error("Expected a direction and a number of steps")),
         };
 
-        let steps = match raw_command.last() {
-            Some(steps) => steps,
-            None => return Err("Cannot extract number of steps".to_string()),
-        };
-
-        Self::raw_to_command(direction, steps)
-    }
-
-    fn raw_to_command(direction: &str, steps: &str) -> Result<Self, String> {
-        let steps = steps
-            .parse::<u32>()
-            .map_err(|err| format!("Error: {}", err))?;
-
-        let command = match direction.to_lowercase().as_ref() {
+        let command = match direction.text.to_lowercase().as_ref() {
             "forward" => Self::Forward(steps),
             "up" => Self::Up(steps),
             "down" => Self::Down(steps),
-            _ => return Err(format!("{} is not a valid direction", direction)),
+            _ => return Err(direction.error("Not a valid direction")),
         };
 
         Ok(command)
     }
 }
 
-pub fn solve(input: &mut Input) -> Result<u32, String> {
-    let commands = input
-        .text
-        .lines()
-        .map(|l| l.trim())
-        .filter(|l| !l.is_empty())
-        .map(|line| {
-            SubmarineCommand::translate(line)
-                .map_err(|err| format!("Cannot translate raw command because: {}", err))
-        })
-        .collect::<Result<Vec<SubmarineCommand>, String>>()?;
-
-    let mut submarine = Submarine::new();
-
-    for command in commands {
-        if input.is_part_one() {
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<SubmarineCommand>;
+    type Answer = u32;
+
+    fn parse(text: &str) -> Result<Vec<SubmarineCommand>, AocError> {
+        lines(text).map(SubmarineCommand::parse).collect()
+    }
+
+    fn part_one(commands: &Vec<SubmarineCommand>) -> Result<u32, AocError> {
+        let mut submarine = Submarine::new();
+        for &command in commands {
             submarine.take_command(command);
-        } else {
-            submarine.take_aim_command(command);
         }
+        Ok(submarine.x * submarine.y)
     }
 
-    Ok(submarine.x * submarine.y)
+    fn part_two(commands: &Vec<SubmarineCommand>) -> Result<u32, AocError> {
+        let mut submarine = Submarine::new();
+        for &command in commands {
+            submarine.take_aim_command(command);
+        }
+        Ok(submarine.x * submarine.y)
+    }
 }
 
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::{test_expected_answers, test_part_one, test_part_one_error, test_part_two};
 
     let text = r#"
     forward 5
@@ -109,8 +101,10 @@ pub fn tests() {
 
     test_part_one!(text => 150);
     test_part_two!(text => 900);
+    test_part_one_error!("forward 5\nbackward 2" => "Line 2, column 1: Not a valid direction backward");
+    test_part_one_error!("forward 5\ndown" => "Line 2, column 1: Expected a direction and a number of steps down");
+    test_part_one_error!("up -3" => "Line 1, column 4: Not a valid u32 -3");
 
     let file_input = include_str!("day02_input.txt");
-    test_part_one!(file_input => 1636725);
-    test_part_two!(file_input => 1872757425);
+    test_expected_answers!(2021, 2, file_input);
 }
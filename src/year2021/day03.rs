@@ -1,4 +1,7 @@
-use crate::input::Input;
+use crate::common::bits::BitReader;
+use crate::common::parse_lines::lines;
+use crate::error::AocError;
+use crate::solution::Solution;
 
 fn set_lowest_bits(n: u8) -> u16 {
     u16::MAX >> (u16::BITS as u16 - u16::from(n))
@@ -11,65 +14,77 @@ fn is_mostly_set(values: &[u16], index: usize) -> bool {
         >= 0
 }
 
-pub fn solve(input: &mut Input) -> Result<u32, String> {
-    let bit_size = input
-        .text
-        .lines()
-        .find(|l| !l.is_empty())
-        .take()
-        .map(|l| l.trim().len())
-        .unwrap_or_default();
-
-    if bit_size == 0 {
-        return Err("Error: bit size is 0".to_string());
-    }
+/// Report is the diagnostic report, with the bit size shared by its numbers.
+pub struct Report {
+    bit_size: usize,
+    numbers: Vec<u16>,
+}
 
-    if input.is_part_two() {
-        return solve_two(input.text, bit_size);
-    }
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Report;
+    type Answer = u32;
 
-    let bit_counter = &mut [0; 16][0..bit_size];
-    for line in input.text.lines() {
-        for (i, c) in line.trim().bytes().rev().enumerate() {
-            bit_counter[i] += if c == b'1' { 1 } else { -1 };
+    fn parse(text: &str) -> Result<Report, AocError> {
+        let lines = lines(text).collect::<Vec<_>>();
+        let bit_size = lines.first().map_or(0, |line| line.text.len());
+        if bit_size == 0 {
+            return Err(AocError::InvalidInput("Error: bit size is 0".to_string()));
         }
+
+        let numbers = lines
+            .into_iter()
+            .map(|line| {
+                if bit_size > u16::BITS as usize {
+                    return Err(line.error("Expected at most 16 bits"));
+                } else if line.text.len() != bit_size {
+                    let message = format!("Expected {} bits, found {}", bit_size, line.text.len());
+                    return Err(line.error(&message));
+                }
+                let number = BitReader::from_spans([line], 2)?.read(bit_size)?;
+                Ok(number as u16)
+            })
+            .collect::<Result<Vec<u16>, AocError>>()?;
+
+        Ok(Report { bit_size, numbers })
     }
 
-    let gamma: u16 = bit_counter
-        .iter()
-        .enumerate()
-        .filter(|(_, &c)| c >= 0)
-        .map(|(i, _)| 1 << i)
-        .sum();
+    fn part_one(report: &Report) -> Result<u32, AocError> {
+        let bit_counter = &mut [0; 16][0..report.bit_size];
+        for number in report.numbers.iter() {
+            for (i, counter) in bit_counter.iter_mut().enumerate() {
+                *counter += if number & (1 << i) != 0 { 1 } else { -1 };
+            }
+        }
 
-    let epsilon: u16 = !gamma & set_lowest_bits(bit_size as u8);
+        let gamma: u16 = bit_counter
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c >= 0)
+            .map(|(i, _)| 1 << i)
+            .sum();
 
-    Ok(u32::from(gamma * epsilon))
-}
+        let epsilon: u16 = !gamma & set_lowest_bits(report.bit_size as u8);
+
+        Ok(u32::from(gamma) * u32::from(epsilon))
+    }
+
+    fn part_two(report: &Report) -> Result<u32, AocError> {
+        let mut numbers = report.numbers.clone();
 
-fn solve_two(input: &str, bitsize: usize) -> Result<u32, String> {
-    // create numbers
-    let mut numbers = input
-        .lines()
-        .map(|l| l.trim())
-        .filter(|l| !l.is_empty())
-        .map(|line| {
-            u16::from_str_radix(line, 2)
-                .map_err(|_| format!("Byte {} is not a binary integer", line))
-        })
-        .collect::<Result<Vec<u16>, _>>()?;
-
-    let oxygen = keep_one_by_criteria(&mut numbers, bitsize, true)?;
-    let co2 = keep_one_by_criteria(&mut numbers, bitsize, false)?;
-
-    Ok(u32::from(oxygen) * u32::from(co2))
+        let oxygen = keep_one_by_criteria(&mut numbers, report.bit_size, true)?;
+        let co2 = keep_one_by_criteria(&mut numbers, report.bit_size, false)?;
+
+        Ok(u32::from(oxygen) * u32::from(co2))
+    }
 }
 
 fn keep_one_by_criteria(
     numbers: &mut [u16],
     bitsize: usize,
     want_most: bool,
-) -> Result<u16, String> {
+) -> Result<u16, AocError> {
     let mut candidates_count = numbers.len();
 
     for i in (0..bitsize).rev() {
@@ -89,12 +104,14 @@ fn keep_one_by_criteria(
         }
     }
 
-    Err("Bit criteria did not result in single number".to_string())
+    Err(AocError::Unsolvable(
+        "Bit criteria did not result in single number".to_string(),
+    ))
 }
 
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::{test_expected_answers, test_part_one, test_part_two};
 
     let text = r#"
     00100
@@ -113,4 +130,17 @@ pub fn tests() {
 
     test_part_one!(text => 198);
     test_part_two!(text => 230);
+
+    // the product of both rates does not fit in the u16 of each rate
+    let file_input = include_str!("day03_input.txt");
+    test_expected_answers!(2021, 3, file_input);
+
+    assert_eq!(
+        Day::parse("101\n1101").err(),
+        Some(AocError::parse(2, 1, "1101", "Expected 3 bits, found 4"))
+    );
+    assert_eq!(
+        Day::parse("101\n121").err(),
+        Some(AocError::parse(2, 2, "2", "Invalid base 2 digit"))
+    );
 }
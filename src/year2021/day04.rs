@@ -1,4 +1,6 @@
-use crate::input::Input;
+use crate::common::parse_lines::{sections, Span};
+use crate::error::AocError;
+use crate::solution::Solution;
 use std::fmt;
 
 /// Cell represents a cell of a board. Besides the number it contains the index
@@ -14,6 +16,7 @@ struct Cell {
     row: usize,
 }
 
+#[derive(Clone)]
 struct Board {
     /// The sum of the numbers in the columns
     cols: [u32; 5],
@@ -42,12 +45,12 @@ impl Board {
         }
     }
 
-    fn add_row(&mut self, row: &[u8; 5]) -> Result<(), String> {
+    fn add_row(&mut self, row: &[u8; 5]) -> Result<(), AocError> {
         if self.last_set >= 24 {
-            return Err(format!(
+            return Err(AocError::InvalidInput(format!(
                 "Board already full when trying to insert {:?}",
                 row
-            ));
+            )));
         }
 
         for number in row {
@@ -113,79 +116,91 @@ impl fmt::Display for Board {
     }
 }
 
-pub fn solve(input: &mut Input) -> Result<u32, String> {
-    let mut lines = input.text.lines();
-
-    // extract first line
-    let mut first_line = lines.next();
-    while first_line.is_none() || first_line.unwrap_or("").is_empty() {
-        first_line = lines.next();
-    }
-    let extraction = match first_line {
-        Some(first_line) => row_to_extractions(first_line)?,
-        None => return Err("Cannot parse extraction".to_string()),
-    };
-
-    // extract boards
-    let mut boards = input
-        .text
-        .split("\n\n") // split boards
-        .skip(1) // skip extraction line
-        .map(|board_str| -> Result<Board, String> {
-            let mut board = Board::new();
-            board_str
-                .lines()
-                .try_for_each(|row| -> Result<(), String> {
-                    let numbers = row_to_numbers(row)?;
-                    board.add_row(&numbers)?;
-                    Ok(())
-                })?;
-
-            Ok(board)
-        })
-        .collect::<Result<Vec<Board>, String>>()?;
+/// Bingo holds the extracted numbers, in order, and the boards.
+pub struct Bingo {
+    extraction: Vec<u8>,
+    boards: Vec<Board>,
+}
 
-    let mut winners_left = input.part_values(1, boards.len());
-    for number in extraction {
-        for board in boards.iter_mut() {
-            if !board.has_won && board.try_mark(number) {
-                winners_left -= 1;
-                if winners_left == 0 {
-                    return Ok(board.unmarked_sum() * u32::from(number));
+impl Bingo {
+    /// Marks the extracted numbers until `winners` boards have won, returning
+    /// the score of the last of them.
+    fn score_of_winner(&self, winners: usize) -> Result<u32, AocError> {
+        let mut boards = self.boards.clone();
+        let mut winners_left = winners;
+        for &number in self.extraction.iter() {
+            for board in boards.iter_mut() {
+                if !board.has_won && board.try_mark(number) {
+                    winners_left -= 1;
+                    if winners_left == 0 {
+                        return Ok(board.unmarked_sum() * u32::from(number));
+                    }
                 }
             }
         }
-    }
 
-    Err("No board won".to_string())
+        Err(AocError::Unsolvable("No board won".to_string()))
+    }
 }
 
-fn row_to_extractions(row: &str) -> Result<Vec<u8>, String> {
-    let mut num_vec = Vec::new();
-    let numbers = row.split(',').map(str::trim).filter(|n| !n.is_empty());
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Bingo;
+    type Answer = u32;
+
+    fn parse(text: &str) -> Result<Bingo, AocError> {
+        let sections = sections(text);
+        let (extraction, boards) = match sections
+            .split_first()
+            .map(|(first, boards)| (first.as_slice(), boards))
+        {
+            Some(([extraction], boards)) => (extraction.numbers()?, boards),
+            Some((lines, _)) => return Err(lines[1].error("Expected a blank line")),
+            None => {
+                return Err(AocError::InvalidInput(
+                    "Cannot parse extraction".to_string(),
+                ))
+            }
+        };
+
+        let boards = boards
+            .iter()
+            .map(|rows| {
+                if rows.len() != 5 {
+                    let message = format!("Expected 5 rows, found {}", rows.len());
+                    return Err(rows[0].error(&message));
+                }
+                let mut board = Board::new();
+                for &row in rows {
+                    board.add_row(&row_to_numbers(row)?)?;
+                }
+                Ok(board)
+            })
+            .collect::<Result<Vec<Board>, AocError>>()?;
 
-    for ns in numbers {
-        let n = ns.parse::<u8>().map_err(|err| err.to_string())?;
-        num_vec.push(n);
+        Ok(Bingo { extraction, boards })
     }
 
-    Ok(num_vec)
-}
+    fn part_one(bingo: &Bingo) -> Result<u32, AocError> {
+        bingo.score_of_winner(1)
+    }
 
-fn row_to_numbers(row: &str) -> Result<[u8; 5], String> {
-    let mut numbers = [0_u8; 5];
-    for (i, c) in row.split(' ').filter(|n| !n.is_empty()).enumerate() {
-        let n = c
-            .parse::<u8>()
-            .map_err(|err| format!("Error {} while converting {}", err, c))?;
-        numbers[i] = n;
+    fn part_two(bingo: &Bingo) -> Result<u32, AocError> {
+        bingo.score_of_winner(bingo.boards.len())
     }
+}
 
-    Ok(numbers)
+fn row_to_numbers(row: Span) -> Result<[u8; 5], AocError> {
+    let numbers = row.numbers::<u8>()?;
+    numbers
+        .as_slice()
+        .try_into()
+        .map_err(|_| row.error(&format!("Expected 5 numbers, found {}", numbers.len())))
 }
 
 #[test]
-pub fn test_winning_board() -> Result<(), String> {
+pub fn test_winning_board() -> Result<(), AocError> {
     let input = r#"
     22 13 17 11  0
     8  2 23  4 24
@@ -196,9 +211,7 @@ pub fn test_winning_board() -> Result<(), String> {
     let extraction = [0_u8, 22, 13, 8, 17, 11].iter();
 
     let mut board = Board::new();
-    let lines = input.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
-
-    for line in lines {
+    for line in crate::common::parse_lines::lines(input) {
         let numbers = row_to_numbers(line)?;
         board.add_row(&numbers)?;
     }
@@ -208,8 +221,8 @@ pub fn test_winning_board() -> Result<(), String> {
         won = board.try_mark(*num);
     }
     assert!(won);
-    let expected_rows = vec![0_u32, 57, 72, 47, 72];
-    let expected_cols = vec![31_u32, 37, 64, 57, 59];
+    let expected_rows = [0_u32, 57, 72, 47, 72];
+    let expected_cols = [31_u32, 37, 64, 57, 59];
     for (i, row_value) in board.rows.into_iter().enumerate() {
         assert_eq!(row_value, expected_rows[i]);
     }
@@ -220,7 +233,7 @@ pub fn test_winning_board() -> Result<(), String> {
 }
 
 #[test]
-pub fn test_board() -> Result<(), String> {
+pub fn test_board() -> Result<(), AocError> {
     let input = r#"
     22 13 17 11  0
     8  2 23  4 24
@@ -229,17 +242,15 @@ pub fn test_board() -> Result<(), String> {
     1 12 20 15 19"#;
 
     let mut board = Board::new();
-    let lines = input.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
-
-    for line in lines {
+    for line in crate::common::parse_lines::lines(input) {
         let numbers = row_to_numbers(line)?;
         board.add_row(&numbers)?;
     }
-    let expected_rows = vec![63_u32, 61, 67, 42, 67]
+    let expected_rows = [63_u32, 61, 67, 42, 67]
         .iter()
         .map(|n| *n + 5)
         .collect::<Vec<u32>>();
-    let expected_cols = vec![58_u32, 46, 77, 64, 55]
+    let expected_cols = [58_u32, 46, 77, 64, 55]
         .iter()
         .map(|n| *n + 5)
         .collect::<Vec<u32>>();
@@ -252,11 +263,36 @@ pub fn test_board() -> Result<(), String> {
     Ok(())
 }
 
+#[test]
+pub fn test_parse() {
+    assert_eq!(
+        Day::parse("1,2\n\n1 2 3 4 5\n1 2 3 4").err(),
+        Some(AocError::parse(
+            3,
+            1,
+            "1 2 3 4 5",
+            "Expected 5 rows, found 2"
+        ))
+    );
+    assert_eq!(
+        Day::parse("1,2\n\n1 2 3 4 5\n1 2 3 4\n1 2 3 4 5\n1 2 3 4 5\n1 2 3 4 5").err(),
+        Some(AocError::parse(
+            4,
+            1,
+            "1 2 3 4",
+            "Expected 5 numbers, found 4"
+        ))
+    );
+    assert_eq!(
+        Day::parse("1,x2").err(),
+        Some(AocError::parse(1, 3, "x2", "Not a valid u8"))
+    );
+}
+
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::test_expected_answers;
 
     let file_input = include_str!("day04_input.txt");
-    test_part_one!(file_input => 55770);
-    test_part_two!(file_input => 2980);
+    test_expected_answers!(2021, 4, file_input);
 }
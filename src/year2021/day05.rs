@@ -1,128 +1,88 @@
 use std::collections::HashMap;
 
-use crate::input::Input;
+use crate::common::geometry::Point2;
+use crate::common::parse_lines::{lines, Span};
+use crate::error::AocError;
+use crate::solution::Solution;
 
-type Point = (u32, u32);
+type Point = Point2<i32>;
 
-pub fn solve(input: &mut Input) -> Result<u32, String> {
-    let coords = get_points(input.text)?
-        .into_iter()
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<(Point, Point)>;
+    type Answer = u32;
+
+    fn parse(text: &str) -> Result<Vec<(Point, Point)>, AocError> {
+        lines(text).map(points_from_line).collect()
+    }
+
+    fn part_one(lines: &Vec<(Point, Point)>) -> Result<u32, AocError> {
+        Ok(count_overlaps(lines, false))
+    }
+
+    fn part_two(lines: &Vec<(Point, Point)>) -> Result<u32, AocError> {
+        Ok(count_overlaps(lines, true))
+    }
+}
+
+/// Counts the points where at least two lines overlap, considering diagonal
+/// lines only when `diagonals` is set.
+fn count_overlaps(lines: &[(Point, Point)], diagonals: bool) -> u32 {
+    let coords = lines
+        .iter()
         .filter(|(p1, p2)| {
-            is_straight_line(p1, p2) || (is_perfect_diagonal_line(p1, p2) ^ !input.is_part_two())
+            is_straight_line(p1, p2) || (is_perfect_diagonal_line(p1, p2) == diagonals)
         })
-        .collect::<Vec<(Point, Point)>>();
+        .collect::<Vec<_>>();
 
     let mut point_map: HashMap<Point, u32> = HashMap::new();
     for (p1, p2) in coords {
         // for each point
-        for point in get_line(&p1, &p2) {
+        for point in get_line(p1, p2) {
             // iterate over each point in line between them
             let count = point_map.entry(point).or_insert(0);
             *count += 1;
         }
     }
-    Ok(point_map.into_iter().filter(|(_, c)| *c >= 2).count() as u32)
+    point_map.into_iter().filter(|(_, c)| *c >= 2).count() as u32
 }
 
+/// Returns the points of a horizontal, vertical or diagonal line, ends
+/// included.
 fn get_line(p1: &Point, p2: &Point) -> Vec<Point> {
-    if is_straight_line(p1, p2) {
-        get_straight_line(p1, p2)
-    } else {
-        get_diagonal_line(p1, p2)
-    }
-}
-
-fn get_straight_line(p1: &Point, p2: &Point) -> Vec<Point> {
-    let mut line = Vec::new();
-    if p1.0 != p2.0 {
-        // y are the same (vertical)
-        let start = u32::min(p1.0, p2.0);
-        let end = u32::max(p1.0, p2.0);
-        for x in start..=end {
-            line.push((x, p1.1));
-        }
-    } else {
-        // x are the same (horizontal)
-        let start = u32::min(p1.1, p2.1);
-        let end = u32::max(p1.1, p2.1);
-        for y in start..=end {
-            line.push((p1.0, y));
-        }
-    }
-
-    line
-}
-
-fn get_diagonal_line(p1: &Point, p2: &Point) -> Vec<Point> {
-    let mut line = Vec::new();
-    let mut x = p1.0 as i32;
-    let mut y = p1.1 as i32;
-
-    let dx = ((p2.0 as i32) - x).signum();
-    let dy = ((p2.1 as i32) - y).signum();
-
-    while (x, y) != (p2.0 as i32, p2.1 as i32) {
-        line.push((x as u32, y as u32));
-        x += dx;
-        y += dy;
-    }
-    line.push((x as u32, y as u32));
-
-    line
-}
-
-fn get_points(text: &str) -> Result<Vec<(Point, Point)>, String> {
-    text.lines()
-        .map(|l| l.trim())
-        .filter(|l| !l.is_empty())
-        .map(|line| points_from_line(line, "->"))
-        .collect::<Result<Vec<(Point, Point)>, String>>()
+    let direction = Point::new((p2.x - p1.x).signum(), (p2.y - p1.y).signum());
+    (0..=p1.chebyshev(*p2))
+        .map(|i| *p1 + direction * i)
+        .collect()
 }
 
 fn is_straight_line(start: &Point, end: &Point) -> bool {
-    start.0 == end.0 || start.1 == end.1
+    start.x == end.x || start.y == end.y
 }
 
 fn is_perfect_diagonal_line(start: &Point, end: &Point) -> bool {
-    i32::abs(start.0 as i32 - end.0 as i32) == i32::abs(start.1 as i32 - end.1 as i32)
+    (start.x - end.x).abs() == (start.y - end.y).abs()
 }
 
-fn points_from_line(line: &str, sep: &str) -> Result<(Point, Point), String> {
-    // line is something like x1,y1 -> x2,y2
-    let points = line
-        .split(sep)
-        .map(|str_point| str_points_to_point(str_point.trim()))
-        .collect::<Result<Vec<Point>, String>>()?;
-
-    match points[..] {
-        [start, end, ..] => Ok((start, end)),
-        _ => Err(format!("Invalid points string: {}", line)),
-    }
+/// Parses a line such as `x1,y1 -> x2,y2`.
+fn points_from_line(line: Span) -> Result<(Point, Point), AocError> {
+    let (start, end) = line.pair("->")?;
+    Ok((point(start)?, point(end)?))
 }
 
-fn str_points_to_point(str_points: &str) -> Result<Point, String> {
-    let coords = str_points
-        .split(',')
-        .map(|p| {
-            p.trim()
-                .parse::<u32>()
-                .map_err(|err| format!("Error while parsing ponints: {}", err))
-        })
-        .collect::<Result<Vec<u32>, String>>()?;
-
-    match coords[..] {
-        [x, y, ..] => Ok((x, y)),
-        _ => Err("Invalid point string".to_string()),
-    }
+fn point(span: Span) -> Result<Point, AocError> {
+    let (x, y) = span.pair(",")?;
+    Ok(Point::new(x.parse()?, y.parse()?))
 }
 
 #[test]
 pub fn test_get_straight_line() {
-    let p1: Point = (1, 1);
-    let p2: Point = (1, 3);
+    let p1 = Point::new(1, 1);
+    let p2 = Point::new(1, 3);
 
-    let line = get_straight_line(&p1, &p2);
-    let expected_line: Vec<Point> = vec![(1, 1), (1, 2), (1, 3)];
+    let line = get_line(&p1, &p2);
+    let expected_line = [Point::new(1, 1), Point::new(1, 2), Point::new(1, 3)];
     for (i, p) in line.iter().enumerate() {
         assert_eq!(p, expected_line.get(i).unwrap());
     }
@@ -130,31 +90,34 @@ pub fn test_get_straight_line() {
 
 #[test]
 pub fn test_get_diagonal_line() {
-    let p1: Point = (1, 1);
-    let p2: Point = (3, 3);
+    let p1 = Point::new(3, 3);
+    let p2 = Point::new(1, 1);
 
-    let line = get_diagonal_line(&p1, &p2);
-    let expected_line: Vec<Point> = vec![(1, 1), (2, 2), (3, 3)];
+    let line = get_line(&p1, &p2);
+    let expected_line = [Point::new(3, 3), Point::new(2, 2), Point::new(1, 1)];
     for (i, p) in expected_line.iter().enumerate() {
         assert_eq!(p, line.get(i).unwrap());
     }
 }
 
 #[test]
-pub fn test_points_from_line() -> Result<(), String> {
-    let line = "0,9 -> 5,9";
-    let points = points_from_line(line, "->")?;
-
-    assert_eq!(points.0, (0, 9));
-    assert_eq!(points.1, (5, 9));
+pub fn test_points_from_line() -> Result<(), AocError> {
+    let line = lines("0,9 -> 5,9").next().ok_or("missing line")?;
+    let points = points_from_line(line)?;
+
+    assert_eq!(points.0, Point::new(0, 9));
+    assert_eq!(points.1, Point::new(5, 9));
+    assert_eq!(
+        lines("\n0,9 -> 5;9").map(points_from_line).next(),
+        Some(Err(AocError::parse(2, 8, "5;9", "Expected ,")))
+    );
     Ok(())
 }
 
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::test_expected_answers;
 
     let file_input = include_str!("day05_input.txt");
-    test_part_one!(file_input => 7318);
-    test_part_two!(file_input => 19939);
+    test_expected_answers!(2021, 5, file_input);
 }
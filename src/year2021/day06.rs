@@ -1,28 +1,44 @@
-use crate::input::Input;
+use crate::common::parse_lines::lines;
+use crate::error::AocError;
+use crate::solution::Solution;
 
-pub fn solve(input: &mut Input) -> Result<u64, String> {
-    let mut initial_school = initial_school(input.text)?;
-    for _ in 0..input.part_values(80, 256) {
-        lanternfish_cycle(&mut initial_school)
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = [u64; 9];
+    type Answer = u64;
+
+    fn parse(text: &str) -> Result<[u64; 9], AocError> {
+        initial_school(text)
+    }
+
+    fn part_one(school: &[u64; 9]) -> Result<u64, AocError> {
+        Ok(school_after(school, 80))
+    }
+
+    fn part_two(school: &[u64; 9]) -> Result<u64, AocError> {
+        Ok(school_after(school, 256))
     }
+}
 
-    Ok(initial_school.iter().sum::<u64>() as u64)
+fn school_after(initial_school: &[u64; 9], days: usize) -> u64 {
+    let mut school = *initial_school;
+    for _ in 0..days {
+        lanternfish_cycle(&mut school)
+    }
+
+    school.iter().sum::<u64>()
 }
 
-fn initial_school(text: &str) -> Result<[u64; 9], String> {
+fn initial_school(text: &str) -> Result<[u64; 9], AocError> {
     let mut school = [0_u64; 9];
-    let initial_fishes = text
-        .split(',')
-        .map(|n| n.trim())
-        .filter(|n| !n.is_empty())
-        .map(|n| {
-            n.parse::<usize>()
-                .map_err(|err| format!("Error while parsing: {}", err))
-        })
-        .collect::<Result<Vec<usize>, String>>()?;
-
-    for fish in initial_fishes.into_iter() {
-        school[fish] += 1;
+    for line in lines(text) {
+        for fish in line.words() {
+            let timer = fish.parse::<usize>()?;
+            *school
+                .get_mut(timer)
+                .ok_or_else(|| fish.error("Timer above 8"))? += 1;
+        }
     }
 
     Ok(school)
@@ -34,7 +50,7 @@ fn lanternfish_cycle(school: &mut [u64; 9]) {
 }
 
 #[test]
-pub fn test_example() -> Result<(), String> {
+pub fn test_example() -> Result<(), AocError> {
     let example_input = "3,4,3,1,2";
     let expected_lanternfish = 5934;
 
@@ -48,9 +64,13 @@ pub fn test_example() -> Result<(), String> {
 }
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::test_expected_answers;
 
     let file_input = include_str!("day06_input.txt");
-    test_part_one!(file_input => 360_761);
-    test_part_two!(file_input => 1_632_779_838_045);
+    test_expected_answers!(2021, 6, file_input);
+
+    assert_eq!(
+        initial_school("3,4,\n 9"),
+        Err(AocError::parse(2, 2, "9", "Timer above 8"))
+    );
 }
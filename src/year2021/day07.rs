@@ -1,16 +1,33 @@
-use crate::Input;
+use crate::common::parse_lines::numbers;
+use crate::error::AocError;
+use crate::solution::Solution;
 
-pub fn solve(input: &mut Input) -> Result<u32, String> {
-    let numbers = input
-        .text
-        .split(',')
-        .map(str::parse::<u32>)
-        .collect::<Result<Vec<u32>, _>>()
-        .map_err(|_| "Input is not comma-separated u16 values".to_string())?;
+pub struct Day;
 
-    let max_value = numbers.iter().max().cloned().unwrap_or(0);
+impl Solution for Day {
+    type Parsed = Vec<u32>;
+    type Answer = u32;
+
+    fn parse(text: &str) -> Result<Vec<u32>, AocError> {
+        let numbers = numbers::<u32>(text)?;
+        if numbers.is_empty() {
+            return Err(AocError::InvalidInput("No crab positions".to_string()));
+        }
 
-    let distance_fn: fn(i32) -> i32 = input.part_values(identity, gaussian_sum);
+        Ok(numbers)
+    }
+
+    fn part_one(numbers: &Vec<u32>) -> Result<u32, AocError> {
+        Ok(min_consumption(numbers, identity))
+    }
+
+    fn part_two(numbers: &Vec<u32>) -> Result<u32, AocError> {
+        Ok(min_consumption(numbers, gaussian_sum))
+    }
+}
+
+fn min_consumption(numbers: &[u32], distance_fn: fn(i32) -> i32) -> u32 {
+    let max_value = numbers.iter().max().cloned().unwrap_or(0);
 
     let mut min_consuption = i32::MAX;
     for i in 0..=max_value {
@@ -23,7 +40,7 @@ pub fn solve(input: &mut Input) -> Result<u32, String> {
         }
     }
 
-    Ok(min_consuption as u32)
+    min_consuption as u32
 }
 
 fn identity(distance: i32) -> i32 {
@@ -36,6 +53,7 @@ fn gaussian_sum(distance: i32) -> i32 {
 
 #[test]
 pub fn test_example() -> Result<(), String> {
+    use crate::input::{test_part_one, test_part_two};
     let example_input = "16,1,2,0,4,2,7,1,2,14";
     test_part_one!(example_input => 37);
     test_part_two!(example_input => 145);
@@ -44,11 +62,11 @@ pub fn test_example() -> Result<(), String> {
 }
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one, test_part_one_error, test_part_two_error};
+    use crate::input::{test_expected_answers, test_part_one_error, test_part_two_error};
 
     let file_input = include_str!("day07_input.txt");
-    test_part_one!(file_input => 333_755);
+    test_expected_answers!(2021, 7, file_input);
 
-    test_part_one_error!("" => "Input is not comma-separated u16 values");
-    test_part_two_error!("" => "Input is not comma-separated u16 values");
+    test_part_one_error!("" => "No crab positions");
+    test_part_two_error!("1,x" => "Line 1, column 3: Not a valid u32 x");
 }
@@ -1,36 +1,69 @@
 use std::collections::HashSet;
 
-use crate::Input;
+use crate::common::parse_lines::lines;
+use crate::error::AocError;
+use crate::solution::Solution;
+
+/// Notes holds the signal patterns and the output values of every entry.
+pub struct Notes {
+    patterns: Vec<String>,
+    outputs: Vec<String>,
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Notes;
+    type Answer = u32;
 
-pub fn solve(input: &mut Input) -> Result<u32, String> {
-    let (left, right) = extract_input(input.text)?;
+    fn parse(text: &str) -> Result<Notes, AocError> {
+        let (left, right) = extract_input(text)?;
 
-    let decode_fn: fn(&str, usize, Vec<&str>) -> Option<u32> =
-        input.part_values(decode_one, decode_two);
+        Ok(Notes {
+            patterns: left.into_iter().map(str::to_string).collect(),
+            outputs: right.into_iter().map(str::to_string).collect(),
+        })
+    }
+
+    fn part_one(notes: &Notes) -> Result<u32, AocError> {
+        let left = notes
+            .patterns
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
 
-    if input.is_part_one() {
-        Ok(right
+        Ok(notes
+            .outputs
             .iter()
             .map(|s| {
                 s.split_ascii_whitespace()
-                    .map(|value| decode_fn(value, value.len(), left.clone()))
+                    .map(|value| decode_one(value, value.len(), left.clone()))
                     .filter(|v| v.is_some())
                     .count()
             })
             .sum::<usize>() as u32)
-    } else {
-        let valid_count = right
+    }
+
+    fn part_two(notes: &Notes) -> Result<u32, AocError> {
+        let left = notes
+            .patterns
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        let valid_count = notes
+            .outputs
             .iter()
             .map(|s| {
                 s.split_ascii_whitespace()
-                    .map(|value| decode_fn(value, value.len(), left.clone()))
+                    .map(|value| decode_two(value, value.len(), left.clone()))
                     .map(|v| v.unwrap())
                     .reduce(|a, num| a * 10 + num)
                     .unwrap_or(0)
             })
             .sum::<u32>();
 
-        Ok(valid_count as u32)
+        Ok(valid_count)
     }
 }
 
@@ -73,7 +106,7 @@ fn decode(value: &str, count: usize, input: Vec<HashSet<char>>) -> u32 {
         return digit;
     }
 
-    let digit: HashSet<char> = value.chars().into_iter().collect::<HashSet<_>>();
+    let digit: HashSet<char> = value.chars().collect::<HashSet<_>>();
 
     if digit.len() == 5 {
         if input[0].difference(&digit).count() == 0 {
@@ -92,17 +125,13 @@ fn decode(value: &str, count: usize, input: Vec<HashSet<char>>) -> u32 {
     }
 }
 
-fn extract_input(text: &str) -> Result<(Vec<&str>, Vec<&str>), String> {
-    let (input, output): (Vec<_>, Vec<_>) = text
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(
-            |pattern| match pattern.split('|').collect::<Vec<&str>>()[..] {
-                [input, output, ..] => Ok((input.trim(), output.trim())),
-                _ => Err(format!("Invalid input: {}", pattern)),
-            },
-        )
-        .collect::<Result<Vec<(&str, &str)>, String>>()?
+fn extract_input(text: &str) -> Result<(Vec<&str>, Vec<&str>), AocError> {
+    let (input, output): (Vec<_>, Vec<_>) = lines(text)
+        .map(|line| {
+            let (input, output) = line.pair("|")?;
+            Ok((input.text, output.text))
+        })
+        .collect::<Result<Vec<(&str, &str)>, AocError>>()?
         .into_iter()
         .unzip();
     Ok((input, output))
@@ -110,6 +139,7 @@ fn extract_input(text: &str) -> Result<(Vec<&str>, Vec<&str>), String> {
 
 #[test]
 pub fn test_example() -> Result<(), String> {
+    use crate::input::{test_part_one, test_part_one_error};
     let example_input = r#"
     be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
     edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
@@ -122,13 +152,14 @@ pub fn test_example() -> Result<(), String> {
     egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
     gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce"#;
     test_part_one!(example_input => 26);
+    test_part_one_error!("be cfbegad cbdgef fgaecd" => "Line 1, column 1: Expected | be cfbegad cbdgef fgaecd");
 
     Ok(())
 }
 #[test]
 pub fn tests() {
-    use crate::input::test_part_one;
+    use crate::input::test_expected_answers;
 
     let file_input = include_str!("day08_input.txt");
-    test_part_one!(file_input => 303);
+    test_expected_answers!(2021, 8, file_input);
 }
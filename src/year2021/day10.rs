@@ -1,19 +1,36 @@
-use crate::input::Input;
-
-pub fn solve(input: &mut Input) -> Result<u64, String> {
-    let scores = input
-        .text
-        .lines()
-        .map(|l| l.trim())
-        .filter(|l| !l.is_empty())
-        .map(line_value)
-        .filter(|(_, corrupt)| *corrupt == input.part_values(true, false))
-        .map(|(v, _)| v);
-
-    if input.is_part_one() {
-        Ok(scores.sum::<u64>())
-    } else {
-        let mut scores = scores.collect::<Vec<u64>>();
+use crate::error::AocError;
+use crate::solution::Solution;
+
+pub struct Day;
+
+impl Solution for Day {
+    /// The score of each line, and whether the line is corrupted
+    type Parsed = Vec<(u64, bool)>;
+    type Answer = u64;
+
+    fn parse(text: &str) -> Result<Vec<(u64, bool)>, AocError> {
+        Ok(text
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .map(line_value)
+            .collect())
+    }
+
+    fn part_one(lines: &Vec<(u64, bool)>) -> Result<u64, AocError> {
+        Ok(lines
+            .iter()
+            .filter(|(_, corrupt)| *corrupt)
+            .map(|(v, _)| v)
+            .sum::<u64>())
+    }
+
+    fn part_two(lines: &Vec<(u64, bool)>) -> Result<u64, AocError> {
+        let mut scores = lines
+            .iter()
+            .filter(|(_, corrupt)| !corrupt)
+            .map(|&(v, _)| v)
+            .collect::<Vec<u64>>();
         scores.sort_unstable();
         Ok(scores[scores.len() / 2])
     }
@@ -105,6 +122,7 @@ pub fn test_line_corrupted_value() {
 
 #[test]
 pub fn test_example() -> Result<(), String> {
+    use crate::input::{test_part_one, test_part_two};
     let example_input = r#"
     [({(<(())[]>[[{[]{<()<>>
     [(()[<>])]({[<{<<[]>>(
@@ -125,9 +143,8 @@ pub fn test_example() -> Result<(), String> {
 
 #[test]
 pub fn tests() {
-    use crate::input::test_part_one;
+    use crate::input::test_expected_answers;
 
     let file_input = include_str!("day10_input.txt");
-    test_part_one!(file_input => 464991);
-    //test_part_two!(file_input => 0);
+    test_expected_answers!(2021, 10, file_input);
 }
@@ -1,94 +1,84 @@
-use crate::input::Input;
+use crate::common::grid::{Grid, Position};
+use crate::error::AocError;
+use crate::solution::Solution;
 
-pub fn solve(input: &mut Input) -> Result<u32, String> {
-    let mut board = Grid::parse(input.text)?;
+pub struct Day;
 
-    for step in 1..=input.part_values(100, 1_000_000) {
-        let previous_flashes = board.flashes_count;
-        board.advance();
-        let current_flashes = board.flashes_count;
-        if input.is_part_two() && current_flashes - previous_flashes == 100 {
-            return Ok(step);
-        }
-    }
-
-    Ok(board.flashes_count)
-}
+impl Solution for Day {
+    type Parsed = Octopuses;
+    type Answer = u32;
 
-struct Grid {
-    cells: [u8; Self::SIZE * Self::SIZE],
-    flashes_count: u32,
-}
-
-impl Grid {
-    const SIZE: usize = 10;
-
-    fn parse(s: &str) -> Result<Self, String> {
-        let mut board = Self {
-            cells: [0; Self::SIZE * Self::SIZE],
+    fn parse(text: &str) -> Result<Octopuses, AocError> {
+        let energy = Grid::parse_digits(text)?;
+        if energy.is_empty() {
+            return Err(AocError::InvalidInput("No octopuses".to_string()));
+        }
+        Ok(Octopuses {
+            energy,
             flashes_count: 0,
-        };
-
-        // remove whitespaces and collect in a String
-        let s = s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+        })
+    }
 
-        if s.len() != 100 {
-            return Err("Grid must have 100 cels".to_string());
-        }
-        for (i, c) in s.chars().enumerate() {
-            let n = c.to_string().parse::<u8>().map_err(|err| err.to_string())?;
-            board.cells[i] = n;
+    fn part_one(octopuses: &Octopuses) -> Result<u32, AocError> {
+        let mut octopuses = octopuses.clone();
+        for _ in 0..100 {
+            octopuses.advance();
         }
 
-        Ok(board)
+        Ok(octopuses.flashes_count)
     }
 
-    fn at(&self, x: usize, y: usize) -> u8 {
-        self.cells[x + (y * Self::SIZE)]
-    }
+    fn part_two(octopuses: &Octopuses) -> Result<u32, AocError> {
+        let mut octopuses = octopuses.clone();
+        let count = octopuses.energy.len() as u32;
+        for step in 1..=1_000_000 {
+            let previous_flashes = octopuses.flashes_count;
+            octopuses.advance();
+            if octopuses.flashes_count - previous_flashes == count {
+                return Ok(step);
+            }
+        }
 
-    fn set(&mut self, x: usize, y: usize, value: u8) {
-        self.cells[x + (y * Self::SIZE)] = value;
+        Ok(octopuses.flashes_count)
     }
+}
 
+#[derive(Clone)]
+pub struct Octopuses {
+    energy: Grid<u8>,
+    flashes_count: u32,
+}
+
+impl Octopuses {
     fn advance(&mut self) {
         // increment each cell
-        self.cells.iter_mut().for_each(|c| *c += 1);
-
-        for y in 0..10 {
-            for x in 0..10 {
-                // check for flashes
-                if self.at(x, y) > 9 {
-                    self.flash(x, y);
-                }
+        self.energy.values_mut().for_each(|c| *c += 1);
+
+        for position in self.energy.positions() {
+            // check for flashes
+            if self.energy[position] > 9 {
+                self.flash(position);
             }
         }
     }
 
-    fn flash(&mut self, x: usize, y: usize) {
-        let current_value = self.at(x, y);
+    fn flash(&mut self, position: Position) {
+        let current_value = self.energy[position];
         // already flashed
         if current_value == 0 {
             return;
         }
 
-        self.set(x, y, current_value + 1); // increment position
+        self.energy[position] = current_value + 1; // increment position
 
         if current_value + 1 > 9 {
             // flashed
-            self.set(x, y, 0);
+            self.energy[position] = 0;
             self.flashes_count += 1;
 
-            for dy in -1..=1 {
-                for dx in -1..=1 {
-                    if (dx, dy) != (0, 0) {
-                        let n_x = x as i32 + dx;
-                        let n_y = y as i32 + dy;
-                        if (0..10).contains(&n_x) && (0..10).contains(&n_y) {
-                            self.flash(n_x as usize, n_y as usize);
-                        }
-                    }
-                }
+            let neighbours = self.energy.neighbours8(position).collect::<Vec<_>>();
+            for neighbour in neighbours {
+                self.flash(neighbour);
             }
         }
     }
@@ -116,9 +106,8 @@ pub fn test_example() -> Result<(), String> {
 
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::test_expected_answers;
 
     let file_input = include_str!("day11_input.txt");
-    test_part_one!(file_input => 1617);
-    test_part_two!(file_input => 258);
+    test_expected_answers!(2021, 11, file_input);
 }
@@ -1,11 +1,27 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::input::Input;
+use crate::common::parse_lines::{lines, Span};
+use crate::common::search::count_paths;
+use crate::error::AocError;
+use crate::solution::Solution;
 
-pub fn solve(input: &mut Input) -> Result<u32, String> {
-    let graph = Graph::parse(input.text)?;
+pub struct Day;
 
-    Ok(graph.travel(Node::Start, Vec::new(), 0, input.part_values(true, false)))
+impl Solution for Day {
+    type Parsed = Graph;
+    type Answer = u32;
+
+    fn parse(text: &str) -> Result<Graph, AocError> {
+        Graph::parse(text)
+    }
+
+    fn part_one(graph: &Graph) -> Result<u32, AocError> {
+        Ok(graph.count_paths(false))
+    }
+
+    fn part_two(graph: &Graph) -> Result<u32, AocError> {
+        Ok(graph.count_paths(true))
+    }
 }
 
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
@@ -17,20 +33,18 @@ enum Node {
 }
 
 #[derive(Default, Debug)]
-struct Graph {
+pub struct Graph {
     nodes: HashMap<Node, HashSet<Node>>,
 }
 
 impl Graph {
-    fn parse(text: &str) -> Result<Self, String> {
+    fn parse(text: &str) -> Result<Self, AocError> {
         let mut graph: Graph = Default::default();
 
-        for l in clean_lines(text).into_iter() {
-            let (from, to) = l
-                .split_once('-')
-                .ok_or(format!("Cannot split line {}", l))?;
-            let from_node = Node::parse(from.to_string())?;
-            let to_node = Node::parse(to.to_string())?;
+        for line in lines(text) {
+            let (from, to) = line.pair("-")?;
+            let from_node = Node::parse(from)?;
+            let to_node = Node::parse(to)?;
             graph
                 .nodes
                 .entry(from_node.clone())
@@ -45,57 +59,71 @@ impl Graph {
         Ok(graph)
     }
 
-    fn travel(&self, start: Node, visited: Vec<Node>, depth: u32, visited_twice: bool) -> u32 {
-        if start == Node::End {
-            return depth + 1;
-        }
+    /// Counts the paths from the start to the end visiting small caves at
+    /// most once, except for a single one visited twice if `revisit` is set.
+    fn count_paths(&self, revisit: bool) -> u32 {
+        let start = Walk {
+            cave: Node::Start,
+            visited: Vec::new(),
+            revisited: !revisit,
+        };
+        count_paths(
+            start,
+            |walk| self.next_steps(walk),
+            |walk| walk.cave == Node::End,
+        ) as u32
+    }
 
-        let mut visited_twice = visited_twice;
-        if !matches!(start, Node::Big(_)) {
-            if start == Node::Start && !visited.is_empty()
-                || visited_twice && visited.contains(&start)
-            {
-                return depth;
-            } else if visited.contains(&start) {
-                visited_twice = true;
-            }
+    fn next_steps(&self, walk: &Walk) -> Vec<Walk> {
+        let mut visited = walk.visited.clone();
+        if matches!(walk.cave, Node::Small(_)) {
+            visited.push(walk.cave.clone());
         }
 
-        let mut visited = visited;
-        visited.push(start.clone());
-        depth
-            + self
-                .nodes
-                .get(&start)
-                .unwrap()
-                .iter()
-                .map(|n| self.travel(n.clone(), visited.clone(), depth, visited_twice))
-                .sum::<u32>()
+        self.nodes
+            .get(&walk.cave)
+            .into_iter()
+            .flatten()
+            .filter(|&cave| *cave != Node::Start)
+            .filter_map(|cave| {
+                let revisiting = visited.contains(cave);
+                if revisiting && walk.revisited {
+                    return None;
+                }
+                Some(Walk {
+                    cave: cave.clone(),
+                    visited: visited.clone(),
+                    revisited: walk.revisited || revisiting,
+                })
+            })
+            .collect()
     }
 }
 
+/// Walk is a path through the caves so far, as the cave it reached, the small
+/// caves it went through and whether one of them was visited twice.
+struct Walk {
+    cave: Node,
+    visited: Vec<Node>,
+    revisited: bool,
+}
+
 impl Node {
-    fn parse(node: String) -> Result<Self, String> {
-        match node.as_str() {
+    fn parse(node: Span) -> Result<Self, AocError> {
+        match node.text {
             "start" => Ok(Self::Start),
             "end" => Ok(Self::End),
-            node if node.to_uppercase() == node => Ok(Self::Big(node.to_string())),
-            node if node.to_lowercase() == node => Ok(Self::Small(node.to_string())),
-            invalid_node => Err(format!("Cannot parse node {}", invalid_node)),
+            "" => Err(node.error("Missing cave")),
+            name if name.to_uppercase() == name => Ok(Self::Big(name.to_string())),
+            name if name.to_lowercase() == name => Ok(Self::Small(name.to_string())),
+            _ => Err(node.error("Cave is neither big nor small")),
         }
     }
 }
 
-fn clean_lines(text: &str) -> Vec<&str> {
-    text.lines()
-        .map(|l| l.trim())
-        .filter(|l| !l.is_empty())
-        .collect()
-}
-
 #[test]
 pub fn test_example() -> Result<(), String> {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::{test_part_one, test_part_one_error, test_part_two};
     let example_input = r#"start-A
     start-b
     A-c
@@ -106,15 +134,17 @@ pub fn test_example() -> Result<(), String> {
 
     test_part_one!(example_input => 10);
     test_part_two!(example_input => 36);
+    test_part_one_error!("start-A\nA_end" => "Line 2, column 1: Expected - A_end");
+    test_part_one_error!("start-A\nA-" => "Line 2, column 3: Missing cave ");
+    test_part_one_error!("start-Ab" => "Line 1, column 7: Cave is neither big nor small Ab");
 
     Ok(())
 }
 
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::test_expected_answers;
 
     let file_input = include_str!("day12_input.txt");
-    test_part_one!(file_input => 5920);
-    test_part_two!(file_input => 155477);
+    test_expected_answers!(2021, 12, file_input);
 }
@@ -1,105 +1,105 @@
-use crate::common::recognize_chars;
-use crate::input::Input;
-use std::collections::HashSet;
+use crate::common::geometry::Point2;
+use crate::common::parse_lines::{sections, Span};
+use crate::common::recognize_chars::recognize_points;
+use crate::error::AocError;
+use crate::solution::Solution;
 
 #[derive(Debug)]
-enum Instruction {
-    Point(u32, u32),
+pub enum Instruction {
+    Point(Point2<u32>),
     FoldAlongX(u32),
     FoldAlongY(u32),
 }
 
 impl Instruction {
-    fn from_str(input: &str) -> Result<Self, String> {
-        if input.to_lowercase().starts_with("fold along y") {
-            let foldy = input.replace("fold along y=", "");
-            let y = foldy.parse::<u32>().map_err(|err| {
-                format!("Error while parsing line {}: {}", input, err.to_string())
-            })?;
-            Ok(Self::FoldAlongY(y))
-        } else if input.to_lowercase().starts_with("fold along x") {
-            let foldx = input.replace("fold along x=", "");
-            let x = foldx.parse::<u32>().map_err(|err| {
-                format!("Error while parsing line {}: {}", input, err.to_string())
-            })?;
-            Ok(Self::FoldAlongX(x))
-        } else {
-            let (x, y) = input
-                .trim()
-                .split_once(",")
-                .ok_or(format!("Invalid string {}", input))?;
-            let x = x.parse::<u32>().map_err(|err| {
-                format!("Error while parsing line {}: {}", input, err.to_string())
-            })?;
-            let y = y.parse::<u32>().map_err(|err| {
-                format!("Error while parsing line {}: {}", input, err.to_string())
-            })?;
-            Ok(Self::Point(x, y))
+    /// Parses a dot such as `6,10`.
+    fn point(line: Span) -> Result<Self, AocError> {
+        let (x, y) = line.pair(",")?;
+        Ok(Self::Point(Point2::new(x.parse()?, y.parse()?)))
+    }
+
+    /// Parses a fold such as `fold along y=7`.
+    fn fold(line: Span) -> Result<Self, AocError> {
+        let (axis, position) = line
+            .strip_prefix("fold along ")
+            .ok_or_else(|| line.error("Expected fold along"))?
+            .pair("=")?;
+        match axis.text {
+            "x" => Ok(Self::FoldAlongX(position.parse()?)),
+            "y" => Ok(Self::FoldAlongY(position.parse()?)),
+            _ => Err(axis.error("Expected x or y")),
         }
     }
 }
 
-pub fn solve(input: &mut Input) -> Result<String, String> {
-    let instructions = parse(input.text)?;
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<Instruction>;
+    type Answer = String;
+
+    fn parse(text: &str) -> Result<Vec<Instruction>, AocError> {
+        parse(text)
+    }
+
+    fn part_one(instructions: &Vec<Instruction>) -> Result<String, AocError> {
+        let mut dots = fold(instructions, 1);
+        dots.sort_unstable();
+        dots.dedup();
+        Ok(dots.len().to_string())
+    }
+
+    fn part_two(instructions: &Vec<Instruction>) -> Result<String, AocError> {
+        let dots = fold(instructions, usize::MAX);
+        recognize_points(dots.into_iter().map(|dot| (dot.x as usize, dot.y as usize)))
+    }
+}
+
+/// Returns the dots after applying at most `folds` fold instructions.
+fn fold(instructions: &[Instruction], folds: usize) -> Vec<Point2<u32>> {
     let mut dots = Vec::new();
+    let mut folds_left = folds;
 
     for instruction in instructions {
-        match instruction {
-            Instruction::Point(x, y) => dots.push((x, y)),
+        if folds_left == 0 {
+            break;
+        }
+        match *instruction {
+            Instruction::Point(dot) => dots.push(dot),
             Instruction::FoldAlongX(n) => {
-                for (x, _) in dots.iter_mut() {
-                    if *x > n {
-                        *x = 2 * n - *x;
-                    }
-                }
-                if input.is_part_one() {
-                    break;
+                for dot in dots.iter_mut().filter(|dot| dot.x > n) {
+                    dot.x = 2 * n - dot.x;
                 }
+                folds_left -= 1;
             }
             Instruction::FoldAlongY(n) => {
-                for (_, y) in dots.iter_mut() {
-                    if *y > n {
-                        *y = 2 * n - *y;
-                    }
-                }
-                if input.is_part_one() {
-                    break;
+                for dot in dots.iter_mut().filter(|dot| dot.y > n) {
+                    dot.y = 2 * n - dot.y;
                 }
+                folds_left -= 1;
             }
         }
     }
-    if input.is_part_one() {
-        dots.sort_unstable();
-        dots.dedup();
-        return Ok(dots.len().to_string());
-    }
 
-    let dots = HashSet::<(u32, u32)>::from_iter(dots);
-    let mut code = String::new();
+    dots
+}
 
-    for letter in 0..8 {
-        let mut char_string = String::new();
-        for y in 0..6 {
-            for x in 0..5 {
-                let x_pos = letter * 5 + x;
-                char_string.push(if dots.contains(&(x_pos, y)) { '#' } else { ' ' });
-            }
-            if y != 5 {
-                char_string.push('\n');
-            }
+/// Parses the dots, then after a blank line the folds.
+fn parse(text: &str) -> Result<Vec<Instruction>, AocError> {
+    let sections = sections(text);
+    let (dots, folds) = match sections.as_slice() {
+        [dots, folds] => (dots, folds),
+        _ => {
+            return Err(AocError::InvalidInput(
+                "Expected dots and folds separated by a blank line".to_string(),
+            ))
         }
-        let c = recognize_chars::recognize(&char_string)?;
-        code.push(c);
-    }
-    Ok(code)
-}
+    };
 
-fn parse(text: &str) -> Result<Vec<Instruction>, String> {
-    text.lines()
-        .map(|l| l.trim())
-        .filter(|l| !l.is_empty())
-        .map(Instruction::from_str)
-        .collect::<Result<Vec<Instruction>, String>>()
+    dots.iter()
+        .map(|&line| Instruction::point(line))
+        .chain(folds.iter().map(|&line| Instruction::fold(line)))
+        .collect()
 }
 
 #[test]
@@ -134,9 +134,13 @@ pub fn test_example() -> Result<(), String> {
 
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::test_expected_answers;
 
     let file_input = include_str!("day13_input.txt");
-    test_part_one!(file_input => 704.to_string());
-    test_part_two!(file_input => "HGAJBEHC".to_string());
+    test_expected_answers!(2021, 13, file_input);
+
+    assert_eq!(
+        parse("6,10\n\nfold along z=7").err(),
+        Some(AocError::parse(3, 12, "z", "Expected x or y"))
+    );
 }
@@ -1,74 +1,109 @@
 use std::collections::HashMap;
 
-use crate::input::Input;
+use crate::common::parse_lines::sections;
+use crate::error::AocError;
+use crate::solution::Solution;
 
 type Pair = (char, char);
 
-pub fn solve(input: &mut Input) -> Result<u64, String> {
-    let mut lines = input.text.lines();
+/// Polymer holds the polymer template and the pair insertion rules.
+pub struct Polymer {
+    template: Vec<char>,
+    rules: Vec<(Pair, char)>,
+}
 
-    let polymer_template = lines
-        .next()
-        .ok_or("Cannot read first line")?
-        .chars()
-        .collect::<Vec<_>>();
+pub struct Day;
 
-    lines.next();
+impl Solution for Day {
+    type Parsed = Polymer;
+    type Answer = u64;
 
-    let pairs = lines
-        .map(|ln| {
-            let chars = ln.chars().collect::<Vec<_>>();
-            ((chars[0], chars[1]), chars[6])
-        })
-        .collect::<Vec<_>>();
+    fn parse(text: &str) -> Result<Polymer, AocError> {
+        let sections = sections(text);
+        let (template, rules) = match sections.as_slice() {
+            [template, rules] if template.len() == 1 => (template[0], rules),
+            _ => {
+                return Err(AocError::InvalidInput(
+                    "Expected a template and rules separated by a blank line".to_string(),
+                ))
+            }
+        };
+
+        let template = template.text.chars().collect::<Vec<_>>();
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let (pair, element) = rule.pair("->")?;
+                match (pair.text.as_bytes(), element.text.as_bytes()) {
+                    (&[first, second], &[inserted]) => {
+                        Ok(((first as char, second as char), inserted as char))
+                    }
+                    _ => Err(rule.error("Expected a rule such as AB -> C")),
+                }
+            })
+            .collect::<Result<Vec<_>, AocError>>()?;
+
+        Ok(Polymer { template, rules })
+    }
 
-    let mut pair_map = HashMap::new();
-    // add first step
-    for pair in polymer_template.windows(2) {
-        *pair_map.entry((pair[0], pair[1])).or_default() += 1;
+    fn part_one(polymer: &Polymer) -> Result<u64, AocError> {
+        polymer.grow(10)
     }
 
-    for _ in 0..input.part_values(10, 40) {
-        let mut to_add: Vec<(Pair, u64)> = Vec::new();
-        let mut to_remove: Vec<(Pair, u64)> = Vec::new();
-        for &(new_pair, new_char) in pairs.iter() {
-            if let Some(&count) = pair_map.get(&new_pair) {
-                to_add.push(((new_pair.0, new_char), count));
-                to_add.push(((new_char, new_pair.1), count));
-                to_remove.push((new_pair, count));
-            }
+    fn part_two(polymer: &Polymer) -> Result<u64, AocError> {
+        polymer.grow(40)
+    }
+}
+
+impl Polymer {
+    /// Applies the insertion rules `steps` times, returning the difference
+    /// between the quantities of the most and the least common elements.
+    fn grow(&self, steps: usize) -> Result<u64, AocError> {
+        let polymer_template = &self.template;
+        let mut pair_map = HashMap::new();
+        // add first step
+        for pair in polymer_template.windows(2) {
+            *pair_map.entry((pair[0], pair[1])).or_default() += 1;
         }
-        for (added, count) in to_add {
-            *pair_map.entry(added).or_default() += count;
+
+        for _ in 0..steps {
+            let mut to_add: Vec<(Pair, u64)> = Vec::new();
+            let mut to_remove: Vec<(Pair, u64)> = Vec::new();
+            for &(new_pair, new_char) in self.rules.iter() {
+                if let Some(&count) = pair_map.get(&new_pair) {
+                    to_add.push(((new_pair.0, new_char), count));
+                    to_add.push(((new_char, new_pair.1), count));
+                    to_remove.push((new_pair, count));
+                }
+            }
+            for (added, count) in to_add {
+                *pair_map.entry(added).or_default() += count;
+            }
+            for (removed, count) in to_remove {
+                *pair_map.entry(removed).or_default() -= count;
+            }
         }
-        for (removed, count) in to_remove {
-            *pair_map.entry(removed).or_default() -= count;
+
+        let mut element_freqs: HashMap<char, u64> = HashMap::new();
+        for (key, count) in pair_map.iter() {
+            *element_freqs.entry(key.0).or_default() += count;
+            *element_freqs.entry(key.1).or_default() += count;
         }
-    }
 
-    let mut element_freqs: HashMap<char, u64> = HashMap::new();
-    for (key, count) in pair_map.iter() {
-        *element_freqs.entry(key.0).or_default() += count;
-        *element_freqs.entry(key.1).or_default() += count;
+        // edge elements
+        *element_freqs.entry(polymer_template[0]).or_default() += 1;
+        *element_freqs
+            .entry(polymer_template[polymer_template.len() - 1])
+            .or_default() += 1;
+
+        let most_common = element_freqs.values().max().ok_or_else(|| {
+            AocError::InvalidInput("Cannot find max on element frequencies".to_string())
+        })?;
+        let least_common = element_freqs.values().min().ok_or_else(|| {
+            AocError::InvalidInput("Cannot find max on element frequencies".to_string())
+        })?;
+        Ok((most_common - least_common) / 2)
     }
-
-    // edge elements
-    *element_freqs.entry(polymer_template[0]).or_default() += 1;
-    *element_freqs
-        .entry(polymer_template[polymer_template.len() - 1])
-        .or_default() += 1;
-
-    let most_common = element_freqs
-        .iter()
-        .map(|(_, c)| c)
-        .max()
-        .ok_or("Cannot find max on element frequencies")?;
-    let least_common = element_freqs
-        .iter()
-        .map(|(_, c)| c)
-        .min()
-        .ok_or("Cannot find max on element frequencies")?;
-    Ok((most_common - least_common) / 2)
 }
 
 #[test]
@@ -101,9 +136,18 @@ CN -> C";
 
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::test_expected_answers;
 
     let file_input = include_str!("day14_input.txt");
-    test_part_one!(file_input => 2112);
-    test_part_two!(file_input => 3243771149914);
+    test_expected_answers!(2021, 14, file_input);
+
+    assert_eq!(
+        Day::parse("NNCB\n\nCH -> B\nHHH -> N").err(),
+        Some(AocError::parse(
+            4,
+            1,
+            "HHH -> N",
+            "Expected a rule such as AB -> C"
+        ))
+    );
 }
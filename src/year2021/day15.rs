@@ -1,71 +1,61 @@
-use itertools::Itertools;
-use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashMap},
-};
+use crate::common::geometry::Point2;
+use crate::common::grid::Grid;
+use crate::common::search::astar;
+use crate::error::AocError;
+use crate::solution::Solution;
 
-use crate::input::Input;
+pub struct Day;
 
-type Coord = (i16, i16);
+impl Solution for Day {
+    type Parsed = Grid<u8>;
+    type Answer = u32;
 
-pub fn solve(input: &mut Input) -> Result<u32, String> {
-    let mut grid = parse(input.text);
-    if input.is_part_two() {
-        let (width, height) = grid
-            .keys()
-            .max()
-            .map(|(x, y)| (x + 1, y + 1))
-            .ok_or_else(|| "Cannot expand grid".to_string())?;
-        grid = grid
-            .into_iter()
-            .flat_map(|((x, y), risk)| {
-                (0..5).cartesian_product(0..5).map(move |(tile_x, tile_y)| {
-                    (
-                        (tile_x * width + x, tile_y * height + y),
-                        (risk + tile_x as u32 + tile_y as u32 - 1) % 9 + 1,
-                    )
-                })
-            })
-            .collect();
+    fn parse(text: &str) -> Result<Grid<u8>, AocError> {
+        // a risk of 0 would underflow when tiling, and would make the
+        // heuristic of best_total_risk overestimate
+        let grid = Grid::parse_with(text, |c| match c {
+            '1'..='9' => c.to_digit(10).map(|risk| risk as u8),
+            _ => None,
+        })?;
+        if grid.is_empty() {
+            return Err(AocError::InvalidInput("Empty risk map".to_string()));
+        }
+        Ok(grid)
     }
-    Ok(best_total_risk(&grid))
-}
 
-fn parse(text: &str) -> HashMap<Coord, u32> {
-    text.lines()
-        .map(|l| l.trim())
-        .filter(|l| !l.is_empty())
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(move |(x, c)| ((x as i16, y as i16), c.to_digit(10).unwrap()))
-        })
-        .collect::<HashMap<_, _>>()
-}
+    fn part_one(grid: &Grid<u8>) -> Result<u32, AocError> {
+        best_total_risk(grid)
+    }
 
-fn best_total_risk(grid: &HashMap<Coord, u32>) -> u32 {
-    let mut best_known = HashMap::new();
-    // start from top left
-    let mut queue = BinaryHeap::from([(Reverse(0), 0, 0)]);
-    while let Some((Reverse(total_risk), x, y)) = queue.pop() {
-        let best_known_risk = best_known.entry((x, y)).or_insert(u32::MAX);
-        if total_risk < *best_known_risk {
-            *best_known_risk = total_risk;
-            for (dx, dy) in [(0, 1), (1, 0), (-1, 0), (0, -1)] {
-                let (x, y) = (x + dx, y + dy);
-                if let Some(risk) = grid.get(&(x, y)) {
-                    queue.push((Reverse(total_risk + risk), x, y));
-                }
-            }
-        }
+    fn part_two(grid: &Grid<u8>) -> Result<u32, AocError> {
+        let grid = grid.tile(5, 5, |&risk, (tile_x, tile_y)| {
+            (risk + tile_x as u8 + tile_y as u8 - 1) % 9 + 1
+        });
+        best_total_risk(&grid)
     }
-    best_known[best_known.keys().max().unwrap()]
+}
+
+/// Returns the lowest total risk from the top left to the bottom right. The
+/// heuristic is the distance left, as entering a position costs at least 1.
+fn best_total_risk(grid: &Grid<u8>) -> Result<u32, AocError> {
+    let goal = (grid.width() - 1, grid.height() - 1);
+    let path = astar(
+        (0, 0),
+        |&position| {
+            grid.neighbours4(position)
+                .map(|next| (next, u32::from(grid[next])))
+        },
+        |&position| Point2::from(position).manhattan(goal.into()) as u32,
+        |&position| position == goal,
+    )
+    .ok_or_else(|| AocError::Unsolvable("No path to the bottom right".to_string()))?;
+
+    Ok(path.cost)
 }
 
 #[test]
 pub fn test_example() -> Result<(), String> {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::{test_part_one, test_part_two, test_part_two_error};
     let example_input = "1163751742
     1381373672
     2136511328
@@ -79,15 +69,15 @@ pub fn test_example() -> Result<(), String> {
 
     test_part_one!(example_input => 40);
     test_part_two!(example_input => 315);
+    test_part_two_error!("19\n 01" => "Line 2, column 2: Invalid character 0");
 
     Ok(())
 }
 
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one, test_part_two};
+    use crate::input::test_expected_answers;
 
     let file_input = include_str!("day15_input.txt");
-    test_part_one!(file_input => 673);
-    test_part_two!(file_input => 2893);
+    test_expected_answers!(2021, 15, file_input);
 }
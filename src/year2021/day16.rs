@@ -1,24 +1,333 @@
-use crate::input::Input;
+use crate::common::bits::BitReader;
+use crate::error::AocError;
+use crate::solution::Solution;
+use std::fmt;
+use std::str::FromStr;
 
-pub fn solve(_input: &mut Input) -> Result<u32, String> {
-    Err("Not implemented".to_string())
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Packet;
+    type Answer = u64;
+
+    fn parse(text: &str) -> Result<Packet, AocError> {
+        text.parse::<Packet>()
+    }
+
+    fn part_one(packet: &Packet) -> Result<u64, AocError> {
+        Ok(packet.version_sum())
+    }
+
+    fn part_two(packet: &Packet) -> Result<u64, AocError> {
+        packet.evaluate()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Operator {
+    fn from_type_id(type_id: u64) -> Result<Self, AocError> {
+        Ok(match type_id {
+            0 => Self::Sum,
+            1 => Self::Product,
+            2 => Self::Minimum,
+            3 => Self::Maximum,
+            5 => Self::GreaterThan,
+            6 => Self::LessThan,
+            7 => Self::EqualTo,
+            _ => {
+                return Err(AocError::InvalidInput(format!(
+                    "Invalid operator type {}",
+                    type_id
+                )))
+            }
+        })
+    }
+
+    /// Tells whether the operator applies to that many sub-packets:
+    /// comparisons need two and the others at least one.
+    const fn accepts(self, count: usize) -> bool {
+        match self {
+            Self::GreaterThan | Self::LessThan | Self::EqualTo => count == 2,
+            _ => count > 0,
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Sum => "sum",
+            Self::Product => "product",
+            Self::Minimum => "min",
+            Self::Maximum => "max",
+            Self::GreaterThan => "gt",
+            Self::LessThan => "lt",
+            Self::EqualTo => "eq",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketKind {
+    /// A literal value packet (type ID 4)
+    Literal(u64),
+    /// An operator packet applied to its sub-packets
+    Operator(Operator, Vec<Packet>),
+}
+
+/// Packet is a decoded BITS packet together with all of its sub-packets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub version: u8,
+    pub kind: PacketKind,
+}
+
+impl Packet {
+    const LITERAL_TYPE_ID: u64 = 4;
+    /// Operator packets nest at most this deep, which keeps the recursion in
+    /// decoding, evaluating and formatting packets within the stack.
+    const MAX_DEPTH: usize = 256;
+
+    /// Decodes the packet nested inside `depth` operator packets.
+    fn decode(reader: &mut BitReader, depth: usize) -> Result<Self, AocError> {
+        let start = reader.position();
+        let version = reader.read(3)? as u8;
+        let type_id = reader.read(3)?;
+
+        let kind = if type_id == Self::LITERAL_TYPE_ID {
+            PacketKind::Literal(Self::decode_literal(reader)?)
+        } else {
+            let operator = Operator::from_type_id(type_id)?;
+            if depth >= Self::MAX_DEPTH {
+                return Err(AocError::InvalidInput(format!(
+                    "Operator packets nested more than {} deep at position {}",
+                    Self::MAX_DEPTH,
+                    start
+                )));
+            }
+            let subpackets = Self::decode_subpackets(reader, depth + 1)?;
+            if !operator.accepts(subpackets.len()) {
+                return Err(AocError::InvalidInput(format!(
+                    "Operator {} cannot apply to {} sub-packets at position {}",
+                    operator.name(),
+                    subpackets.len(),
+                    start
+                )));
+            }
+            PacketKind::Operator(operator, subpackets)
+        };
+
+        Ok(Self { version, kind })
+    }
+
+    fn decode_literal(reader: &mut BitReader) -> Result<u64, AocError> {
+        let mut value: u64 = 0;
+        loop {
+            let group = reader.read(5)?;
+            if value.leading_zeros() < 4 {
+                return Err(AocError::InvalidInput(
+                    "Literal value does not fit in 64 bits".to_string(),
+                ));
+            }
+            value = (value << 4) | (group & 0xF);
+            if group & 0x10 == 0 {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn decode_subpackets(reader: &mut BitReader, depth: usize) -> Result<Vec<Self>, AocError> {
+        let mut subpackets = Vec::new();
+
+        if !reader.read_flag()? {
+            // length type 0: the next 15 bits are the total length in bits
+            let length = reader.read(15)? as usize;
+            let mut subreader = reader.take(length)?;
+            while !subreader.is_empty() {
+                subpackets.push(Self::decode(&mut subreader, depth)?);
+            }
+        } else {
+            // length type 1: the next 11 bits are the number of sub-packets
+            let count = reader.read(11)?;
+            for _ in 0..count {
+                subpackets.push(Self::decode(reader, depth)?);
+            }
+        }
+
+        Ok(subpackets)
+    }
+
+    pub fn version_sum(&self) -> u64 {
+        let children = match &self.kind {
+            PacketKind::Literal(_) => 0,
+            PacketKind::Operator(_, subpackets) => subpackets.iter().map(Self::version_sum).sum(),
+        };
+        u64::from(self.version) + children
+    }
+
+    /// Evaluates the expression of the packet. Decoding made sure that each
+    /// operator has a valid number of sub-packets.
+    pub fn evaluate(&self) -> Result<u64, AocError> {
+        let (operator, subpackets) = match &self.kind {
+            PacketKind::Literal(value) => return Ok(*value),
+            PacketKind::Operator(operator, subpackets) => (*operator, subpackets),
+        };
+
+        let values = subpackets
+            .iter()
+            .map(Self::evaluate)
+            .collect::<Result<Vec<u64>, AocError>>()?;
+
+        let overflow = || AocError::Unsolvable(format!("Overflow evaluating {}", operator.name()));
+        match operator {
+            Operator::Sum => values
+                .iter()
+                .try_fold(0_u64, |acc, &v| acc.checked_add(v))
+                .ok_or_else(overflow),
+            Operator::Product => values
+                .iter()
+                .try_fold(1_u64, |acc, &v| acc.checked_mul(v))
+                .ok_or_else(overflow),
+            Operator::Minimum => Ok(values.into_iter().min().unwrap_or_default()),
+            Operator::Maximum => Ok(values.into_iter().max().unwrap_or_default()),
+            Operator::GreaterThan => Ok(u64::from(values[0] > values[1])),
+            Operator::LessThan => Ok(u64::from(values[0] < values[1])),
+            Operator::EqualTo => Ok(u64::from(values[0] == values[1])),
+        }
+    }
+}
+
+impl FromStr for Packet {
+    type Err = AocError;
+
+    /// Decodes the outermost packet of a hex-encoded transmission. Trailing
+    /// bits after the packet are padding and are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut reader = BitReader::from_hex(s)?;
+        Self::decode(&mut reader, 0)
+    }
+}
+
+impl fmt::Display for Packet {
+    /// Formats the packet as an expression, e.g. `sum(1, product(2, 3))`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            PacketKind::Literal(value) => write!(f, "{}", value),
+            PacketKind::Operator(operator, subpackets) => {
+                write!(f, "{}(", operator.name())?;
+                for (i, subpacket) in subpackets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", subpacket)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[test]
+pub fn test_decode() -> Result<(), AocError> {
+    let literal = "D2FE28".parse::<Packet>()?;
+    assert_eq!(
+        literal,
+        Packet {
+            version: 6,
+            kind: PacketKind::Literal(2021)
+        }
+    );
+
+    let operator = "38006F45291200".parse::<Packet>()?;
+    assert_eq!(operator.version, 1);
+    assert_eq!(operator.to_string(), "lt(10, 20)");
+
+    let operator = "EE00D40C823060".parse::<Packet>()?;
+    assert_eq!(operator.version, 7);
+    assert_eq!(operator.to_string(), "max(1, 2, 3)");
+
+    assert!("D2FE".parse::<Packet>().is_err());
+    assert!("D2FEXX".parse::<Packet>().is_err());
+    // lt(10) from the lt(10, 20) packet announcing a single sub-packet
+    assert_eq!(
+        "3A005450".parse::<Packet>(),
+        Err(AocError::InvalidInput(
+            "Operator lt cannot apply to 1 sub-packets at position 0".to_string()
+        ))
+    );
+
+    // sum(sum(...(1)...)), each sum announcing a single sub-packet
+    let nested = |depth: usize| {
+        let mut bits = "000000100000000001".repeat(depth) + "00010000001";
+        bits.push_str(&"0".repeat((4 - bits.len() % 4) % 4));
+        (0..bits.len())
+            .step_by(4)
+            .map(|i| format!("{:X}", u8::from_str_radix(&bits[i..i + 4], 2).unwrap()))
+            .collect::<String>()
+    };
+    assert_eq!(
+        nested(Packet::MAX_DEPTH).parse::<Packet>()?.evaluate(),
+        Ok(1)
+    );
+    assert_eq!(
+        nested(Packet::MAX_DEPTH + 1).parse::<Packet>(),
+        Err(AocError::InvalidInput(format!(
+            "Operator packets nested more than 256 deep at position {}",
+            18 * Packet::MAX_DEPTH
+        )))
+    );
+
+    let literal = |value| Packet {
+        version: 0,
+        kind: PacketKind::Literal(value),
+    };
+    let product = Packet {
+        version: 0,
+        kind: PacketKind::Operator(Operator::Product, vec![literal(u64::MAX), literal(2)]),
+    };
+    assert_eq!(
+        product.evaluate(),
+        Err(AocError::Unsolvable(
+            "Overflow evaluating product".to_string()
+        ))
+    );
+
+    Ok(())
 }
 
 #[test]
 pub fn test_example() -> Result<(), String> {
-    use crate::input::{test_part_one_error, test_part_two_error};
-    let example_input = "";
+    use crate::input::{test_part_one, test_part_two};
+
+    test_part_one!("8A004A801A8002F478" => 16);
+    test_part_one!("620080001611562C8802118E34" => 12);
+    test_part_one!("C0015000016115A2E0802F182340" => 23);
+    test_part_one!("A0016C880162017C3686B18A3D4780" => 31);
+
+    test_part_two!("C200B40A82" => 3);
+    test_part_two!("04005AC33890" => 54);
+    test_part_two!("880086C3E88112" => 7);
+    test_part_two!("CE00C43D881120" => 9);
+    test_part_two!("D8005AC2A8F0" => 1);
+    test_part_two!("F600BC2D8F" => 0);
+    test_part_two!("9C005AC2F8F0" => 0);
+    test_part_two!("9C0141080250320F1802104A08" => 1);
 
-    test_part_one_error!(example_input => "Not implemented".to_string());
-    test_part_two_error!(example_input => "Not implemented".to_string());
     Ok(())
 }
 
 #[test]
 pub fn tests() {
-    use crate::input::{test_part_one_error, test_part_two_error};
+    use crate::input::test_expected_answers;
 
     let file_input = include_str!("day16_input.txt");
-    test_part_one_error!(file_input => "Not implemented".to_string());
-    test_part_two_error!(file_input => "Not implemented".to_string());
+    test_expected_answers!(2021, 16, file_input);
 }
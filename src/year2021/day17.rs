@@ -1,16 +1,310 @@
-use crate::input::Input;
+#[cfg(test)]
+use crate::common::geometry::Point2;
+use crate::common::intervals::IntervalSet;
+use crate::common::parse_lines::lines;
+use crate::error::AocError;
+use crate::solution::Solution;
+use std::ops::{Range, RangeInclusive};
 
-pub fn solve(_input: &mut Input) -> Result<u32, String> {
-    Err("Not implemented".to_string())
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Target;
+    type Answer = u64;
+
+    fn parse(text: &str) -> Result<Target, AocError> {
+        Target::parse(text)
+    }
+
+    fn part_one(target: &Target) -> Result<u64, AocError> {
+        target
+            .hit_counts()?
+            .filter(|&(_, count)| count > 0)
+            .map(|(vy, _)| apex(vy))
+            .max()
+            .ok_or_else(|| AocError::Unsolvable("No velocity hits the target".to_string()))
+    }
+
+    fn part_two(target: &Target) -> Result<u64, AocError> {
+        Ok(target.hit_counts()?.map(|(_, count)| count).sum())
+    }
+}
+
+/// Target is the rectangular target area, bounds included. Coordinates are
+/// read as `i32` and kept as `i64`, so that the positions reached by any
+/// velocity that may hit the target fit.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Target {
+    x_min: i64,
+    x_max: i64,
+    y_min: i64,
+    y_max: i64,
+}
+
+impl Target {
+    fn parse(text: &str) -> Result<Self, AocError> {
+        let mut lines = lines(text);
+        let line = lines
+            .next()
+            .ok_or_else(|| AocError::InvalidInput("Missing target area".to_string()))?;
+        if let Some(extra) = lines.next() {
+            return Err(extra.error("Unexpected line after the target area"));
+        }
+
+        let prefix = "target area:";
+        let (x_range, y_range) = line
+            .strip_prefix(prefix)
+            .ok_or_else(|| line.error(&format!("Expected {}", prefix)))?
+            .trim()
+            .pair(",")?;
+        let x_range = x_range.range::<i32>("x")?;
+        let y_range = y_range.range::<i32>("y")?;
+
+        // either bound may come first
+        Ok(Self {
+            x_min: i64::from(*x_range.start().min(x_range.end())),
+            x_max: i64::from(*x_range.start().max(x_range.end())),
+            y_min: i64::from(*y_range.start().min(y_range.end())),
+            y_max: i64::from(*y_range.start().max(y_range.end())),
+        })
+    }
+
+    /// Returns each vertical speed that may hit the target, along with the
+    /// number of horizontal speeds that hit it together with it.
+    ///
+    /// Vertically the probe comes back down through `y = 0` with speed
+    /// `-vy - 1`, so once `vy` exceeds the largest coordinate of the target
+    /// the probe steps over it, unless it hits on `y = 0` itself. That takes
+    /// moving horizontally still, which bounds `vy` by [`Self::resting_step`].
+    fn hit_counts(&self) -> Result<impl Iterator<Item = (i64, u64)> + '_, AocError> {
+        let y_has_zero = self.y_min <= 0 && 0 <= self.y_max;
+        if y_has_zero && self.has_resting_x() {
+            return Err(AocError::Unsolvable(
+                "Infinitely many velocities hit the target".to_string(),
+            ));
+        }
+
+        let resting_step = self.resting_step();
+        let mut vy_max = self.y_min.abs().max(self.y_max.abs());
+        if y_has_zero {
+            vy_max = vy_max.max(resting_step / 2);
+        }
+
+        Ok((self.y_min.min(0)..=vy_max).map(move |vy| {
+            let mut hits = IntervalSet::new();
+            for range in self.steps_in_rows(vy) {
+                if range.is_empty() {
+                    continue;
+                }
+                // past the resting step, the same speeds hit at every step
+                for step in range.start.min(resting_step)..range.end.min(resting_step + 1) {
+                    if let Some(vx) = self.speeds_in_columns(step) {
+                        hits.insert(vx);
+                    }
+                }
+            }
+            (vy, hits.len())
+        }))
+    }
+
+    /// Returns the step from which the probe can only be in the columns of
+    /// the target once it stopped moving horizontally: a probe still moving
+    /// at step `t` is more than `t * t / 2` away from the launcher.
+    fn resting_step(&self) -> i64 {
+        let x_bound = self.x_min.abs().max(self.x_max.abs());
+        first(0, x_bound + 2, |t| t * t > 2 * x_bound)
+    }
+
+    /// Returns the steps at which a probe launched with vertical speed `vy`
+    /// is within the rows of the target, as it goes up then as it comes down.
+    fn steps_in_rows(&self, vy: i64) -> [Range<i64>; 2] {
+        let (y_min, y_max) = (i128::from(self.y_min), i128::from(self.y_max));
+        let y = |step| y_after(vy, step);
+
+        // the probe goes up before step `peak` and down from it
+        let peak = vy.max(0) + 1;
+        let mut end = peak * 2;
+        while y(end) >= y_min {
+            end *= 2;
+        }
+
+        [
+            first(1, peak, |t| y(t) >= y_min)..first(1, peak, |t| y(t) > y_max),
+            first(peak, end, |t| y(t) <= y_max)..first(peak, end, |t| y(t) < y_min),
+        ]
+    }
+
+    /// Returns the horizontal speeds that put the probe within the columns of
+    /// the target at `step`, if any.
+    fn speeds_in_columns(&self, step: i64) -> Option<RangeInclusive<i64>> {
+        let x_bound = self.x_min.abs().max(self.x_max.abs());
+        let x = |vx| x_after(vx, step);
+        let low = first(-x_bound, x_bound + 1, |vx| x(vx) >= self.x_min);
+        let high = first(-x_bound, x_bound + 1, |vx| x(vx) > self.x_max);
+        (low < high).then(|| low..=high - 1)
+    }
+
+    /// Tells whether the probe can stop moving horizontally inside the target,
+    /// in which case it keeps falling through the whole column of the target.
+    fn has_resting_x(&self) -> bool {
+        // the probe rests `triangular(|vx|)` away from the launcher
+        has_triangular(self.x_min.max(0), self.x_max)
+            || has_triangular((-self.x_max).max(0), -self.x_min)
+    }
+
+    #[cfg(test)]
+    fn is_hit(&self, vx: i64, vy: i64) -> bool {
+        let mut position = Point2::new(0, 0);
+        let mut velocity = Point2::new(vx, vy);
+
+        // once falling below the target the probe can never come back up
+        while velocity.y >= 0 || position.y >= self.y_min {
+            position += velocity;
+            velocity -= Point2::new(velocity.x.signum(), 1);
+            if (self.x_min..=self.x_max).contains(&position.x)
+                && (self.y_min..=self.y_max).contains(&position.y)
+            {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// Returns the highest y position reached when launching with vertical speed `vy`.
+fn apex(vy: i64) -> u64 {
+    triangular(vy.max(0)) as u64
+}
+
+/// Returns the x position after `step` steps when launching with horizontal
+/// speed `vx`, which grows with `vx`.
+fn x_after(vx: i64, step: i64) -> i64 {
+    let moving = step.min(vx.abs());
+    vx.signum() * (vx.abs() * moving - triangular(moving - 1))
+}
+
+/// Returns the y position after `step` steps when launching with vertical
+/// speed `vy`.
+fn y_after(vy: i64, step: i64) -> i128 {
+    let (vy, step) = (i128::from(vy), i128::from(step));
+    vy * step - step * (step - 1) / 2
+}
+
+/// Returns the first value within `low..high` for which the predicate holds,
+/// or `high`, the predicate holding for every value after the first.
+fn first(mut low: i64, mut high: i64, predicate: impl Fn(i64) -> bool) -> i64 {
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if predicate(middle) {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    low
+}
+
+/// Returns `1 + 2 + ... + n`, the distance covered by a speed of `n` until
+/// it decays to 0.
+const fn triangular(n: i64) -> i64 {
+    n * (n + 1) / 2
+}
+
+/// Tells whether a triangular number lies within `min..=max`, `min` being
+/// non-negative and `max` within `i32`.
+fn has_triangular(min: i64, max: i64) -> bool {
+    triangular(first(0, 1 << 31, |n| triangular(n) >= min)) <= max
+}
+
+#[test]
+pub fn test_parse() {
+    assert_eq!(
+        Target::parse("target area: x=20..30, y=-10..-5"),
+        Ok(Target {
+            x_min: 20,
+            x_max: 30,
+            y_min: -10,
+            y_max: -5
+        })
+    );
+    let far = Target::parse("target area: x=2000000000..2000000001, y=-1..1");
+    assert_eq!(far.map(|target| target.has_resting_x()), Ok(false));
+    let left = Target::parse("target area: x=-2147483648..-2147450880, y=-1..1");
+    assert_eq!(left.map(|target| target.has_resting_x()), Ok(true));
+    assert_eq!(apex(2_000_000_000), 2_000_000_001_000_000_000);
+    assert_eq!(apex(-5), 0);
+    assert_eq!(
+        Target::parse("target area: x=30..20, y=-5..-10"),
+        Target::parse("target area: x=20..30, y=-10..-5")
+    );
+    assert_eq!(
+        Target::parse("target area: x=20..30"),
+        Err(AocError::parse(1, 14, "x=20..30", "Expected ,"))
+    );
+    assert_eq!(
+        Target::parse("target area: x=20..30, y=-10..b"),
+        Err(AocError::parse(1, 31, "b", "Not a valid i32"))
+    );
+    assert_eq!(
+        Target::parse("target area: x=20..30, z=-10..-5"),
+        Err(AocError::parse(1, 24, "z=-10..-5", "Expected y="))
+    );
+}
+
+#[test]
+pub fn test_bounds() -> Result<(), AocError> {
+    let targets = [
+        "target area: x=20..30, y=-10..-5",
+        "target area: x=-30..-20, y=-10..-5",
+        "target area: x=20..30, y=5..10",
+        "target area: x=-12..-7, y=3..8",
+        "target area: x=11..14, y=-3..2",
+    ];
+
+    for text in targets {
+        let target = Target::parse(text)?;
+        let hits = (-100..=100)
+            .flat_map(|vx| (-100..=100).map(move |vy| (vx, vy)))
+            .filter(|&(vx, vy)| target.is_hit(vx, vy))
+            .collect::<Vec<_>>();
+        for (vy, count) in target.hit_counts()? {
+            let brute_force = hits.iter().filter(|&&(_, y)| y == vy).count();
+            assert_eq!(count, brute_force as u64, "{} vy={}", text, vy);
+        }
+        let total = target.hit_counts()?.map(|(_, count)| count).sum::<u64>();
+        assert_eq!(total, hits.len() as u64, "{}", text);
+    }
+
+    let resting = Target::parse("target area: x=5..7, y=-3..2")?;
+    assert_eq!(
+        resting.hit_counts().map(|counts| counts.count()),
+        Err(AocError::Unsolvable(
+            "Infinitely many velocities hit the target".to_string()
+        ))
+    );
+
+    Ok(())
 }
 
 #[test]
 pub fn test_example() -> Result<(), String> {
-    use crate::input::{test_part_one_error, test_part_two_error};
-    let example_input = "";
+    use crate::input::{test_part_one, test_part_two};
+    let example_input = "target area: x=20..30, y=-10..-5";
+    let mirrored_input = "target area: x=-30..-20, y=-10..-5";
+
+    test_part_one!(example_input => 45);
+    test_part_two!(example_input => 112);
+    test_part_one!(mirrored_input => 45);
+    test_part_two!(mirrored_input => 112);
+
+    let wide_input = "target area: x=20..100000, y=-10..-5";
+    test_part_one!(wide_input => 45);
+    test_part_two!(wide_input => 895_864);
+    let far_input = "target area: x=2000000000..2000000001, y=-1..1";
+    test_part_one!(far_input => 30_517_578);
+    test_part_two!(far_input => 15);
 
-    test_part_one_error!(example_input => "Not implemented".to_string());
-    test_part_two_error!(example_input => "Not implemented".to_string());
     Ok(())
 }
 
@@ -19,6 +313,6 @@ pub fn tests() {
     use crate::input::{test_part_one_error, test_part_two_error};
 
     let file_input = include_str!("day17_input.txt");
-    test_part_one_error!(file_input => "Not implemented".to_string());
-    test_part_two_error!(file_input => "Not implemented".to_string());
+    test_part_one_error!(file_input => "Missing target area");
+    test_part_two_error!(file_input => "Missing target area");
 }
@@ -1,16 +1,462 @@
-use crate::input::Input;
+use crate::common::parse_lines::{lines, Span};
+use crate::error::AocError;
+use crate::solution::Solution;
+use std::fmt;
+use std::iter::{Enumerate, Peekable, Sum};
+use std::ops::Add;
+use std::str::{Chars, FromStr};
 
-pub fn solve(_input: &mut Input) -> Result<u32, String> {
-    Err("Not implemented".to_string())
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<SnailfishNumber>;
+    type Answer = u64;
+
+    fn parse(text: &str) -> Result<Vec<SnailfishNumber>, AocError> {
+        let numbers = lines(text)
+            .map(SnailfishNumber::from_span)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if numbers.is_empty() {
+            return Err(AocError::InvalidInput(
+                "No snailfish numbers provided".to_string(),
+            ));
+        }
+
+        Ok(numbers)
+    }
+
+    fn part_one(numbers: &Vec<SnailfishNumber>) -> Result<u64, AocError> {
+        Ok(SnailfishNumber::try_sum(numbers.iter().cloned())?.magnitude())
+    }
+
+    fn part_two(numbers: &Vec<SnailfishNumber>) -> Result<u64, AocError> {
+        let mut max_magnitude = 0;
+        for (i, left) in numbers.iter().enumerate() {
+            for (j, right) in numbers.iter().enumerate() {
+                if i != j {
+                    let sum = left.clone().try_add(right.clone())?;
+                    max_magnitude = max_magnitude.max(sum.magnitude());
+                }
+            }
+        }
+        Ok(max_magnitude)
+    }
+}
+
+/// SnailfishNumber is either a regular number or a pair of snailfish numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnailfishNumber {
+    Regular(u32),
+    Pair(Box<SnailfishNumber>, Box<SnailfishNumber>),
+}
+
+impl SnailfishNumber {
+    /// Pairs nested inside this many pairs explode.
+    const EXPLODE_DEPTH: usize = 4;
+    /// Regular numbers greater or equal to this value split.
+    const SPLIT_THRESHOLD: u32 = 10;
+
+    fn pair(left: Self, right: Self) -> Self {
+        Self::Pair(Box::new(left), Box::new(right))
+    }
+
+    /// Adds the numbers and reduces the result.
+    pub fn try_add(self, other: Self) -> Result<Self, AocError> {
+        let mut sum = Self::pair(self, other);
+        sum.reduce()?;
+        Ok(sum)
+    }
+
+    /// Adds up the numbers from left to right.
+    pub fn try_sum<I: IntoIterator<Item = Self>>(numbers: I) -> Result<Self, AocError> {
+        let mut numbers = numbers.into_iter();
+        let first = numbers.next().ok_or_else(|| {
+            AocError::InvalidInput("Cannot sum an empty list of snailfish numbers".to_string())
+        })?;
+        numbers.try_fold(first, Self::try_add)
+    }
+
+    /// Repeatedly applies the first applicable action (explode, then split)
+    /// until the number is reduced.
+    pub fn reduce(&mut self) -> Result<(), AocError> {
+        while self.explode()? || self.split() {}
+        Ok(())
+    }
+
+    /// Explodes the leftmost pair nested inside four pairs, if any.
+    /// Returns whether a pair exploded.
+    pub fn explode(&mut self) -> Result<bool, AocError> {
+        Ok(self.explode_at(0)?.is_some())
+    }
+
+    /// Explodes the leftmost deep enough pair below `self`, returning the
+    /// values still to be added to the regular numbers on its left and right.
+    fn explode_at(&mut self, depth: usize) -> Result<Option<Carry>, AocError> {
+        let (left, right) = match self {
+            Self::Regular(_) => return Ok(None),
+            Self::Pair(left, right) => (left, right),
+        };
+
+        if depth >= Self::EXPLODE_DEPTH {
+            if let (Self::Regular(l), Self::Regular(r)) = (left.as_ref(), right.as_ref()) {
+                let carry = (Some(*l), Some(*r));
+                *self = Self::Regular(0);
+                return Ok(Some(carry));
+            }
+        }
+
+        if let Some((carry_left, carry_right)) = left.explode_at(depth + 1)? {
+            if let Some(value) = carry_right {
+                right.add_to_leftmost(value)?;
+            }
+            return Ok(Some((carry_left, None)));
+        }
+
+        if let Some((carry_left, carry_right)) = right.explode_at(depth + 1)? {
+            if let Some(value) = carry_left {
+                left.add_to_rightmost(value)?;
+            }
+            return Ok(Some((None, carry_right)));
+        }
+
+        Ok(None)
+    }
+
+    fn add_to_leftmost(&mut self, value: u32) -> Result<(), AocError> {
+        match self {
+            Self::Regular(n) => *n = Self::checked_add(*n, value)?,
+            Self::Pair(left, _) => left.add_to_leftmost(value)?,
+        }
+        Ok(())
+    }
+
+    fn add_to_rightmost(&mut self, value: u32) -> Result<(), AocError> {
+        match self {
+            Self::Regular(n) => *n = Self::checked_add(*n, value)?,
+            Self::Pair(_, right) => right.add_to_rightmost(value)?,
+        }
+        Ok(())
+    }
+
+    fn checked_add(n: u32, value: u32) -> Result<u32, AocError> {
+        n.checked_add(value).ok_or_else(|| {
+            AocError::InvalidInput(format!(
+                "Regular number overflow adding {} to {} in an explosion",
+                value, n
+            ))
+        })
+    }
+
+    /// Splits the leftmost regular number greater or equal to 10, if any.
+    /// Returns whether a number was split.
+    pub fn split(&mut self) -> bool {
+        match self {
+            Self::Regular(n) if *n >= Self::SPLIT_THRESHOLD => {
+                let (left, right) = (*n / 2, n.div_ceil(2));
+                *self = Self::pair(Self::Regular(left), Self::Regular(right));
+                true
+            }
+            Self::Regular(_) => false,
+            Self::Pair(left, right) => left.split() || right.split(),
+        }
+    }
+
+    /// Returns the magnitude, which fits in a u64 since parsed numbers are
+    /// never nested deeper than a sum can explode.
+    pub fn magnitude(&self) -> u64 {
+        match self {
+            Self::Regular(n) => u64::from(*n),
+            Self::Pair(left, right) => 3 * left.magnitude() + 2 * right.magnitude(),
+        }
+    }
+
+    /// Parses the number of the span, such as a line of the input.
+    pub fn from_span(span: Span) -> Result<Self, AocError> {
+        let mut parser = Parser {
+            span,
+            chars: span.text.chars().enumerate().peekable(),
+        };
+        let number = parser.number(0)?;
+
+        match parser.chars.next() {
+            Some((i, c)) => Err(parser.error(i, c, "Unexpected")),
+            None => Ok(number),
+        }
+    }
+}
+
+/// The values still to be added left and right of an exploded pair
+type Carry = (Option<u32>, Option<u32>);
+
+/// Parser reads a snailfish number character by character, keeping track of
+/// the columns for the errors.
+struct Parser<'a> {
+    span: Span<'a>,
+    chars: Peekable<Enumerate<Chars<'a>>>,
+}
+
+impl Parser<'_> {
+    /// Parses the number nested inside `depth` pairs.
+    fn number(&mut self, depth: usize) -> Result<SnailfishNumber, AocError> {
+        match self.chars.next() {
+            // a sum of parsed numbers must explode its pairs before going deeper
+            Some((i, '[')) if depth > SnailfishNumber::EXPLODE_DEPTH => Err(self.error(
+                i,
+                '[',
+                &format!(
+                    "Pair nested inside more than {} pairs",
+                    SnailfishNumber::EXPLODE_DEPTH
+                ),
+            )),
+            Some((_, '[')) => {
+                let left = self.number(depth + 1)?;
+                self.expect(',')?;
+                let right = self.number(depth + 1)?;
+                self.expect(']')?;
+                Ok(SnailfishNumber::pair(left, right))
+            }
+            Some((i, c)) if c.is_ascii_digit() => {
+                let mut digits = c.to_string();
+                while let Some(&(_, c)) = self.chars.peek().filter(|(_, c)| c.is_ascii_digit()) {
+                    digits.push(c);
+                    self.chars.next();
+                }
+                let value = digits.parse().map_err(|_| {
+                    let column = self.span.column + i;
+                    AocError::parse(self.span.line, column, &digits, "Regular number too large")
+                })?;
+                Ok(SnailfishNumber::Regular(value))
+            }
+            Some((i, c)) => Err(self.error(i, c, "Unexpected")),
+            None => Err(self.end_error("Unexpected end of snailfish number")),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), AocError> {
+        let message = format!("Expected '{}', found", expected);
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((i, c)) => Err(self.error(i, c, &message)),
+            None => Err(self.end_error(&format!("Expected '{}' before the end", expected))),
+        }
+    }
+
+    /// Returns an error located at the character of index `i` of the span.
+    fn error(&self, i: usize, c: char, message: &str) -> AocError {
+        AocError::parse(
+            self.span.line,
+            self.span.column + i,
+            &c.to_string(),
+            message,
+        )
+    }
+
+    fn end_error(&self, message: &str) -> AocError {
+        let column = self.span.column + self.span.text.chars().count();
+        AocError::parse(self.span.line, column, "", message)
+    }
+}
+
+impl FromStr for SnailfishNumber {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let span = Span {
+            line: 1,
+            column: 1,
+            text: s,
+        };
+        Self::from_span(span.trim())
+    }
+}
+
+impl fmt::Display for SnailfishNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Regular(n) => write!(f, "{}", n),
+            Self::Pair(left, right) => write!(f, "[{},{}]", left, right),
+        }
+    }
+}
+
+impl Add for SnailfishNumber {
+    type Output = Self;
+
+    /// Adds the numbers and reduces the result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a regular number overflows while reducing, see
+    /// [`SnailfishNumber::try_add`].
+    fn add(self, rhs: Self) -> Self::Output {
+        self.try_add(rhs).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+impl Sum for SnailfishNumber {
+    /// Adds up the numbers from left to right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is empty, since there is no zero snailfish number,
+    /// or if a regular number overflows, see [`SnailfishNumber::try_sum`].
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self::try_sum(iter).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+#[cfg(test)]
+fn snailfish(s: &str) -> SnailfishNumber {
+    s.parse().unwrap()
+}
+
+#[test]
+pub fn test_parse() {
+    let text = "[[1,2],[[3,4],5]]";
+    let number = snailfish(text);
+
+    assert_eq!(number.to_string(), text);
+    assert_eq!(
+        "[[1,2],[3,4]".parse::<SnailfishNumber>(),
+        Err(AocError::parse(1, 13, "", "Expected ']' before the end"))
+    );
+    assert_eq!(
+        "[1;2]".parse::<SnailfishNumber>(),
+        Err(AocError::parse(1, 3, ";", "Expected ',', found"))
+    );
+    assert_eq!(
+        "[1,2]]".parse::<SnailfishNumber>(),
+        Err(AocError::parse(1, 6, "]", "Unexpected"))
+    );
+    assert_eq!(
+        "[1,99999999999]".parse::<SnailfishNumber>(),
+        Err(AocError::parse(
+            1,
+            4,
+            "99999999999",
+            "Regular number too large"
+        ))
+    );
+    // pairs nested inside four pairs only appear while adding
+    assert_eq!(
+        "[[[[[[1,2],3],4],5],6],7]".parse::<SnailfishNumber>(),
+        Err(AocError::parse(
+            1,
+            6,
+            "[",
+            "Pair nested inside more than 4 pairs"
+        ))
+    );
+}
+
+#[test]
+pub fn test_overflow() {
+    let mut number = snailfish("[[[[[4294967295,1],1],1],1],1]");
+    assert_eq!(number.explode(), Ok(true));
+
+    let mut number = snailfish("[[[[4294967295,[1,1]],1],1],1]");
+    assert_eq!(
+        number.explode(),
+        Err(AocError::InvalidInput(
+            "Regular number overflow adding 1 to 4294967295 in an explosion".to_string()
+        ))
+    );
+}
+
+#[test]
+pub fn test_explode() {
+    let examples = [
+        ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+        ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+        ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+        (
+            "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+            "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+        ),
+        (
+            "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+        ),
+    ];
+
+    for (before, after) in examples {
+        let mut number = snailfish(before);
+        assert_eq!(number.explode(), Ok(true));
+        assert_eq!(number.to_string(), after);
+    }
+}
+
+#[test]
+pub fn test_reduction_steps() -> Result<(), AocError> {
+    let mut number = snailfish("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
+
+    assert!(number.explode()?);
+    assert_eq!(number.to_string(), "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]");
+    assert!(number.explode()?);
+    assert_eq!(number.to_string(), "[[[[0,7],4],[15,[0,13]]],[1,1]]");
+    assert!(!number.explode()?);
+    assert!(number.split());
+    assert_eq!(number.to_string(), "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]");
+    assert!(number.split());
+    assert_eq!(number.to_string(), "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]");
+    assert!(number.explode()?);
+    assert_eq!(number.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+    assert!(!number.explode()?);
+    assert!(!number.split());
+
+    let sum = snailfish("[[[[4,3],4],4],[7,[[8,4],9]]]") + snailfish("[1,1]");
+    assert_eq!(sum, number);
+    let sum = snailfish("[[[[4,3],4],4],[7,[[8,4],9]]]").try_add(snailfish("[1,1]"))?;
+    assert_eq!(sum, number);
+
+    Ok(())
+}
+
+#[test]
+pub fn test_sum() -> Result<(), AocError> {
+    let numbers = ["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]", "[6,6]"]
+        .into_iter()
+        .map(snailfish)
+        .collect::<Vec<_>>();
+
+    let sum = numbers[..4].iter().cloned().sum::<SnailfishNumber>();
+    assert_eq!(sum.to_string(), "[[[[1,1],[2,2]],[3,3]],[4,4]]");
+    let sum = numbers[..5].iter().cloned().sum::<SnailfishNumber>();
+    assert_eq!(sum.to_string(), "[[[[3,0],[5,3]],[4,4]],[5,5]]");
+    let sum = SnailfishNumber::try_sum(numbers)?;
+    assert_eq!(sum.to_string(), "[[[[5,0],[7,4]],[5,5]],[6,6]]");
+    assert!(SnailfishNumber::try_sum(Vec::new()).is_err());
+
+    Ok(())
+}
+
+#[test]
+pub fn test_magnitude() {
+    assert_eq!(snailfish("[[1,2],[[3,4],5]]").magnitude(), 143);
+    assert_eq!(
+        snailfish("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]").magnitude(),
+        3488
+    );
 }
 
 #[test]
 pub fn test_example() -> Result<(), String> {
-    use crate::input::{test_part_one_error, test_part_two_error};
-    let example_input = "";
+    use crate::input::{test_part_one, test_part_two};
+    let example_input = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+    [[[5,[2,8]],4],[5,[[9,9],0]]]
+    [6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+    [[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+    [[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+    [[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+    [[[[5,4],[7,7]],8],[[8,3],8]]
+    [[9,3],[[9,9],[6,[4,9]]]]
+    [[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+    [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]";
+
+    test_part_one!(example_input => 4140);
+    test_part_two!(example_input => 3993);
 
-    test_part_one_error!(example_input => "Not implemented".to_string());
-    test_part_two_error!(example_input => "Not implemented".to_string());
     Ok(())
 }
 
@@ -19,6 +465,12 @@ pub fn tests() {
     use crate::input::{test_part_one_error, test_part_two_error};
 
     let file_input = include_str!("day18_input.txt");
-    test_part_one_error!(file_input => "Not implemented".to_string());
-    test_part_two_error!(file_input => "Not implemented".to_string());
+    test_part_one_error!(file_input => "No snailfish numbers provided");
+    test_part_two_error!(file_input => "No snailfish numbers provided");
+}
+
+#[test]
+#[should_panic(expected = "Regular number overflow")]
+pub fn test_add_overflow() {
+    let _ = snailfish("[[[4294967295,[1,1]],1],1]") + snailfish("1");
 }
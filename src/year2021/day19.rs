@@ -1,17 +1,420 @@
-use crate::input::Input;
+use std::collections::{HashMap, HashSet};
 
-pub fn solve(_input: &mut Input) -> Result<u32, String> {
-    Err("Not implemented".to_string())
+use crate::common::geometry::{Point3, Rotation};
+use crate::common::parse_lines::lines;
+use crate::error::AocError;
+use crate::solution::Solution;
+
+type Point = Point3<i32>;
+
+/// Minimum number of shared beacons for two scanners to be aligned.
+const MIN_OVERLAP: usize = 12;
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<PlacedScanner>;
+    type Answer = u32;
+
+    /// Aligning the scanners is the bulk of the work and is shared by both
+    /// parts, so it happens right after parsing.
+    fn parse(text: &str) -> Result<Vec<PlacedScanner>, AocError> {
+        let scanners = parse(text)?;
+        align(&scanners)
+    }
+
+    fn part_one(placed: &Vec<PlacedScanner>) -> Result<u32, AocError> {
+        let beacons = placed
+            .iter()
+            .flat_map(|scanner| scanner.beacons.iter())
+            .collect::<HashSet<_>>();
+        Ok(beacons.len() as u32)
+    }
+
+    fn part_two(placed: &Vec<PlacedScanner>) -> Result<u32, AocError> {
+        let mut max_distance = 0;
+        for a in placed.iter() {
+            for b in placed.iter() {
+                max_distance = max_distance.max(a.position.manhattan(b.position) as u32);
+            }
+        }
+        Ok(max_distance)
+    }
+}
+
+/// Scanner is the report of a single scanner, with beacons relative to itself.
+#[derive(Debug)]
+struct Scanner {
+    id: u32,
+    beacons: Vec<Point>,
+    /// The sorted squared distances between each pair of beacons, which do
+    /// not depend on the orientation of the scanner
+    fingerprint: Vec<i64>,
+}
+
+impl Scanner {
+    fn new(id: u32, beacons: Vec<Point>) -> Self {
+        let mut fingerprint = Vec::new();
+        for (i, a) in beacons.iter().enumerate() {
+            for b in beacons[i + 1..].iter() {
+                let offset: [i32; 3] = (*a - *b).into();
+                fingerprint.push(offset.iter().map(|&c| i64::from(c).pow(2)).sum());
+            }
+        }
+        fingerprint.sort_unstable();
+
+        Self {
+            id,
+            beacons,
+            fingerprint,
+        }
+    }
+
+    /// Tells whether the two scanners share enough beacon distances to
+    /// possibly share `MIN_OVERLAP` beacons.
+    fn may_overlap(&self, other: &Self) -> bool {
+        let needed = MIN_OVERLAP * (MIN_OVERLAP - 1) / 2;
+        let (mut i, mut j, mut shared) = (0, 0, 0);
+        while i < self.fingerprint.len() && j < other.fingerprint.len() {
+            match self.fingerprint[i].cmp(&other.fingerprint[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    shared += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        shared >= needed
+    }
+}
+
+/// PlacedScanner is a scanner whose position and beacons are expressed in
+/// the coordinates of scanner 0.
+pub struct PlacedScanner {
+    index: usize,
+    position: Point,
+    beacons: Vec<Point>,
+}
+
+/// Tries to place `scanner` relative to the already placed `reference` by
+/// finding a rotation under which `MIN_OVERLAP` beacons share the same offset.
+fn try_place(reference: &PlacedScanner, scanner: &Scanner) -> Option<(Point, Vec<Point>)> {
+    for rotation in Rotation::all() {
+        let rotated = scanner
+            .beacons
+            .iter()
+            .map(|&beacon| rotation.apply(beacon))
+            .collect::<Vec<_>>();
+
+        let mut offsets: HashMap<Point, usize> = HashMap::new();
+        for a in reference.beacons.iter() {
+            for b in rotated.iter() {
+                let offset = *a - *b;
+                let count = offsets.entry(offset).or_default();
+                *count += 1;
+                if *count >= MIN_OVERLAP {
+                    let beacons = rotated.iter().map(|&b| b + offset).collect();
+                    return Some((offset, beacons));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Places every scanner relative to scanner 0, returning an error naming the
+/// scanners that do not overlap with any placed one.
+fn align(scanners: &[Scanner]) -> Result<Vec<PlacedScanner>, AocError> {
+    let first = scanners
+        .first()
+        .ok_or_else(|| AocError::InvalidInput("No scanners provided".to_string()))?;
+
+    let mut placed = vec![PlacedScanner {
+        index: 0,
+        position: Point::default(),
+        beacons: first.beacons.clone(),
+    }];
+    let mut unplaced = (1..scanners.len()).collect::<Vec<_>>();
+    // each placed scanner is used once as a reference for all the unplaced ones
+    let mut next_reference = 0;
+
+    while !unplaced.is_empty() && next_reference < placed.len() {
+        let reference = &placed[next_reference];
+        let reference_scanner = &scanners[reference.index];
+        let mut newly_placed = Vec::new();
+
+        unplaced.retain(|&index| {
+            let scanner = &scanners[index];
+            if !reference_scanner.may_overlap(scanner) {
+                return true;
+            }
+            match try_place(reference, scanner) {
+                Some((position, beacons)) => {
+                    newly_placed.push(PlacedScanner {
+                        index,
+                        position,
+                        beacons,
+                    });
+                    false
+                }
+                None => true,
+            }
+        });
+
+        placed.extend(newly_placed);
+        next_reference += 1;
+    }
+
+    if unplaced.is_empty() {
+        Ok(placed)
+    } else {
+        let ids = unplaced
+            .iter()
+            .map(|&index| scanners[index].id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        Err(AocError::Unsolvable(format!(
+            "Cannot align scanners {}: they share fewer than {} beacons with scanner {} and its neighbours",
+            ids, MIN_OVERLAP, first.id
+        )))
+    }
+}
+
+fn parse(text: &str) -> Result<Vec<Scanner>, AocError> {
+    let mut scanners = Vec::new();
+    let mut current: Option<(u32, Vec<Point>)> = None;
+
+    for line in lines(text) {
+        if let Some(header) = line.strip_prefix("--- scanner ") {
+            let id = header
+                .strip_suffix("---")
+                .ok_or_else(|| line.error("Invalid scanner header"))?
+                .trim()
+                .parse::<u32>()?;
+            if let Some((id, beacons)) = current.replace((id, Vec::new())) {
+                scanners.push(Scanner::new(id, beacons));
+            }
+        } else {
+            let (_, beacons) = current
+                .as_mut()
+                .ok_or_else(|| line.error("Beacon before any scanner header"))?;
+            match line.numbers::<i32>()?[..] {
+                [x, y, z] => beacons.push(Point::new(x, y, z)),
+                _ => return Err(line.error("Expected 3 coordinates")),
+            }
+        }
+    }
+
+    if let Some((id, beacons)) = current {
+        scanners.push(Scanner::new(id, beacons));
+    }
+
+    Ok(scanners)
+}
+
+#[cfg(test)]
+fn scanner_reports(world: &[Point], scanners: &[([i32; 3], usize)]) -> String {
+    let rotations = Rotation::all();
+    let mut text = String::new();
+
+    for (id, (position, rotation)) in scanners.iter().enumerate() {
+        text.push_str(&format!("--- scanner {} ---\n", id));
+        for beacon in world {
+            let relative = *beacon - Point::from(*position);
+            if relative.chebyshev(Point::default()) <= 1000 {
+                let rotated = rotations[*rotation].apply(relative);
+                text.push_str(&format!("{}\n", rotated));
+            }
+        }
+        text.push('\n');
+    }
+
+    text
 }
 
 #[test]
-pub fn test_example() -> Result<(), String> {
-    use crate::input::{test_part_one_error, test_part_two_error};
-    let example_input = "";
+pub fn test_example() {
+    use crate::input::{test_part_one, test_part_two};
+    let example_input = "--- scanner 0 ---
+404,-588,-901
+528,-643,409
+-838,591,734
+390,-675,-793
+-537,-823,-458
+-485,-357,347
+-345,-311,381
+-661,-816,-575
+-876,649,763
+-618,-824,-621
+553,345,-567
+474,580,667
+-447,-329,318
+-584,868,-557
+544,-627,-890
+564,392,-477
+455,729,728
+-892,524,684
+-689,845,-530
+423,-701,434
+7,-33,-71
+630,319,-379
+443,580,662
+-789,900,-551
+459,-707,401
+
+--- scanner 1 ---
+686,422,578
+605,423,415
+515,917,-361
+-336,658,858
+95,138,22
+-476,619,847
+-340,-569,-846
+567,-361,727
+-460,603,-452
+669,-402,600
+729,430,532
+-500,-761,534
+-322,571,750
+-466,-666,-811
+-429,-592,574
+-355,545,-477
+703,-491,-529
+-328,-685,520
+413,935,-424
+-391,539,-444
+586,-435,557
+-364,-763,-893
+807,-499,-711
+755,-354,-619
+553,889,-390
+
+--- scanner 2 ---
+649,640,665
+682,-795,504
+-784,533,-524
+-644,584,-595
+-588,-843,648
+-30,6,44
+-674,560,763
+500,723,-460
+609,671,-379
+-555,-800,653
+-675,-892,-343
+697,-426,-610
+578,704,681
+493,664,-388
+-671,-858,530
+-667,343,800
+571,-461,-707
+-138,-166,112
+-889,563,-600
+646,-828,498
+640,759,510
+-630,509,768
+-681,-892,-333
+673,-379,-804
+-742,-814,-386
+577,-820,562
+
+--- scanner 3 ---
+-589,542,597
+605,-692,669
+-500,565,-823
+-660,373,557
+-458,-679,-417
+-488,449,543
+-626,468,-788
+338,-750,-386
+528,-832,-391
+562,-778,733
+-938,-730,414
+543,643,-506
+-524,371,-870
+407,773,750
+-104,29,83
+378,-903,-323
+-778,-728,485
+426,699,580
+-438,-605,-362
+-469,-447,-387
+509,732,623
+647,635,-688
+-868,-804,481
+614,-800,639
+595,780,-596
+
+--- scanner 4 ---
+727,592,562
+-293,-554,779
+441,611,-461
+-714,465,-776
+-743,427,-804
+-660,-479,-426
+832,-632,460
+927,-485,-438
+408,393,-506
+466,436,-512
+110,16,151
+-258,-428,682
+-393,719,612
+-211,-452,876
+808,-476,-593
+-575,615,604
+-485,667,467
+-680,325,-822
+-627,-443,-432
+872,-547,-609
+833,512,582
+807,604,487
+839,-516,451
+891,-625,532
+-652,-548,-490
+30,-46,-14";
+
+    test_part_one!(example_input => 79);
+    test_part_two!(example_input => 3621);
+}
+
+#[test]
+pub fn test_parse() {
+    assert_eq!(
+        parse("--- scanner 0 ---\n1,2,3\n--- scanner x ---").err(),
+        Some(AocError::parse(3, 13, "x", "Not a valid u32"))
+    );
+    assert_eq!(
+        parse("--- scanner 0 ---\n  1,2,b").err(),
+        Some(AocError::parse(2, 7, "b", "Not a valid i32"))
+    );
+    assert_eq!(
+        parse("--- scanner 0 ---\n  1,2").err(),
+        Some(AocError::parse(2, 3, "1,2", "Expected 3 coordinates"))
+    );
+    assert_eq!(
+        parse("1,2,3").err(),
+        Some(AocError::parse(
+            1,
+            1,
+            "1,2,3",
+            "Beacon before any scanner header"
+        ))
+    );
+}
+
+#[test]
+pub fn test_unaligned() {
+    use crate::input::test_part_one_error;
+
+    let world = (0..20)
+        .map(|i| Point::new(i * 37, i * i, -i * 11))
+        .collect::<Vec<_>>();
+    let text = scanner_reports(&world, &[([0, 0, 0], 0), ([5000, 0, 0], 3), ([0, 0, 0], 5)]);
+    let example_input = text.as_str();
 
-    test_part_one_error!(example_input => "Not implemented".to_string());
-    test_part_two_error!(example_input => "Not implemented".to_string());
-    Ok(())
+    test_part_one_error!(example_input => "Cannot align scanners 1: they share fewer than 12 beacons with scanner 0 and its neighbours");
 }
 
 #[test]
@@ -19,6 +422,6 @@ pub fn tests() {
     use crate::input::{test_part_one_error, test_part_two_error};
 
     let file_input = include_str!("day19_input.txt");
-    test_part_one_error!(file_input => "Not implemented".to_string());
-    test_part_two_error!(file_input => "Not implemented".to_string());
+    test_part_one_error!(file_input => "No scanners provided");
+    test_part_two_error!(file_input => "No scanners provided");
 }
@@ -1,16 +1,234 @@
-use crate::input::Input;
+use std::fmt;
 
-pub fn solve(_input: &mut Input) -> Result<u32, String> {
-    Err("Not implemented".to_string())
+use crate::common::grid::Grid;
+use crate::common::parse_lines::lines;
+use crate::error::AocError;
+use crate::solution::Solution;
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = (Algorithm, Image);
+    type Answer = u32;
+
+    fn parse(text: &str) -> Result<(Algorithm, Image), AocError> {
+        parse(text)
+    }
+
+    fn part_one((algorithm, image): &(Algorithm, Image)) -> Result<u32, AocError> {
+        lit_after_passes(algorithm, image, 2)
+    }
+
+    fn part_two((algorithm, image): &(Algorithm, Image)) -> Result<u32, AocError> {
+        lit_after_passes(algorithm, image, 50)
+    }
+}
+
+/// Returns the number of lit pixels after enhancing the image `passes` times.
+pub fn lit_after_passes(
+    algorithm: &Algorithm,
+    image: &Image,
+    passes: usize,
+) -> Result<u32, AocError> {
+    image
+        .after_passes(algorithm, passes)
+        .lit_count()
+        .map(|count| count as u32)
+        .ok_or_else(|| {
+            AocError::Unsolvable(format!(
+                "Infinitely many pixels are lit after {} passes",
+                passes
+            ))
+        })
+}
+
+/// Algorithm maps each 9 bit neighbourhood to the resulting pixel.
+pub struct Algorithm([bool; 512]);
+
+/// Image is a finite window over an infinite image. Every pixel outside the
+/// window has the same state, the background.
+#[derive(Clone)]
+pub struct Image {
+    pixels: Grid<bool>,
+    background: bool,
+}
+
+impl Image {
+    fn at(&self, x: isize, y: isize) -> bool {
+        match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) => self.pixels.get((x, y)).copied(),
+            _ => None,
+        }
+        .unwrap_or(self.background)
+    }
+
+    /// Applies the algorithm once. The window grows by one pixel on each
+    /// side, since those are the only pixels that can differ from the
+    /// background afterwards.
+    pub fn enhance(&self, algorithm: &Algorithm) -> Self {
+        let (width, height) = (self.pixels.width() + 2, self.pixels.height() + 2);
+        let pixels = Grid::from_fn(width, height, |(x, y)| {
+            let (x, y) = (x as isize, y as isize);
+            let mut index = 0;
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    index = (index << 1) | usize::from(self.at(x - 1 + dx, y - 1 + dy));
+                }
+            }
+            algorithm.0[index]
+        });
+
+        // the background is made of all lit or all dark neighbourhoods
+        let background = algorithm.0[if self.background { 511 } else { 0 }];
+
+        Self { pixels, background }
+    }
+
+    /// Returns the image once the algorithm is applied `passes` times.
+    pub fn after_passes(&self, algorithm: &Algorithm, passes: usize) -> Self {
+        let mut image = self.clone();
+        for _ in 0..passes {
+            image = image.enhance(algorithm);
+        }
+        image
+    }
+
+    /// Returns the number of lit pixels, if finite.
+    pub fn lit_count(&self) -> Option<usize> {
+        if self.background {
+            None
+        } else {
+            Some(self.pixels.values().filter(|&&p| p).count())
+        }
+    }
+}
+
+impl fmt::Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pixels = self.pixels.map(|&p| if p { '#' } else { '.' });
+        write!(f, "{}", pixels)
+    }
+}
+
+fn parse_pixel(c: char) -> Option<bool> {
+    match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    }
+}
+
+/// Parses the image enhancement algorithm, followed by the input image.
+pub fn parse(text: &str) -> Result<(Algorithm, Image), AocError> {
+    let mut lines = lines(text);
+
+    let line = lines
+        .next()
+        .ok_or_else(|| AocError::InvalidInput("Missing image enhancement algorithm".to_string()))?;
+    let algorithm = Grid::parse_lines([line], parse_pixel)?;
+    let algorithm: [bool; 512] = algorithm
+        .values()
+        .copied()
+        .collect::<Vec<_>>()
+        .try_into()
+        .map_err(|v: Vec<bool>| {
+            line.error(&format!(
+                "Algorithm must have 512 pixels, found {}",
+                v.len()
+            ))
+        })?;
+
+    let pixels = Grid::parse_lines(lines, parse_pixel)?;
+    if pixels.is_empty() {
+        return Err(AocError::InvalidInput("Missing input image".to_string()));
+    }
+
+    let image = Image {
+        pixels,
+        background: false,
+    };
+
+    Ok((Algorithm(algorithm), image))
+}
+
+#[cfg(test)]
+const EXAMPLE_INPUT: &str = "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
+
+    #..#.
+    #....
+    ##..#
+    ..#..
+    ..###";
+
+#[test]
+pub fn test_enhance() -> Result<(), AocError> {
+    let (algorithm, image) = parse(EXAMPLE_INPUT)?;
+
+    let image = image.enhance(&algorithm);
+    assert_eq!(
+        image.to_string(),
+        ".##.##.\n#..#.#.\n##.#..#\n####..#\n.#..##.\n..##..#\n...#.#."
+    );
+    let image = image.enhance(&algorithm);
+    assert_eq!(
+        image.to_string(),
+        ".......#.\n.#..#.#..\n#.#...###\n#...##.#.\n#.....#.#\n.#.#####.\n..#.#####\n...##.##.\n....###.."
+    );
+
+    let (algorithm, image) = parse(EXAMPLE_INPUT)?;
+    assert_eq!(
+        image.after_passes(&algorithm, 2).to_string(),
+        image.enhance(&algorithm).enhance(&algorithm).to_string()
+    );
+    assert_eq!(image.after_passes(&algorithm, 0).lit_count(), Some(10));
+    assert_eq!(lit_after_passes(&algorithm, &image, 2), Ok(35));
+
+    assert_eq!(
+        parse("#.#\n\n#.").err(),
+        Some(AocError::parse(
+            1,
+            1,
+            "#.#",
+            "Algorithm must have 512 pixels, found 3"
+        ))
+    );
+    let invalid_image = format!("{}\n\n  #..\n  #.x", ".".repeat(512));
+    assert_eq!(
+        parse(&invalid_image).err(),
+        Some(AocError::parse(4, 5, "x", "Invalid character"))
+    );
+
+    Ok(())
+}
+
+#[test]
+pub fn test_flipping_background() -> Result<(), AocError> {
+    // lights up every dark neighbourhood, and turns off every lit one
+    let algorithm = (0..512)
+        .map(|i| if i == 511 { '.' } else { '#' })
+        .collect::<String>();
+    let (algorithm, image) = parse(&format!("{}\n\n#..\n...\n..#", algorithm))?;
+
+    assert_eq!(
+        lit_after_passes(&algorithm, &image, 1),
+        Err(AocError::Unsolvable(
+            "Infinitely many pixels are lit after 1 passes".to_string()
+        ))
+    );
+    // every pixel is lit after the first pass, so they all go dark again
+    assert_eq!(lit_after_passes(&algorithm, &image, 2), Ok(0));
+    assert_eq!(lit_after_passes(&algorithm, &image, 0), Ok(2));
+
+    Ok(())
 }
 
 #[test]
 pub fn test_example() -> Result<(), String> {
-    use crate::input::{test_part_one_error, test_part_two_error};
-    let example_input = "";
+    use crate::input::{test_part_one, test_part_two};
+
+    test_part_one!(EXAMPLE_INPUT => 35);
+    test_part_two!(EXAMPLE_INPUT => 3351);
 
-    test_part_one_error!(example_input => "Not implemented".to_string());
-    test_part_two_error!(example_input => "Not implemented".to_string());
     Ok(())
 }
 
@@ -19,6 +237,6 @@ pub fn tests() {
     use crate::input::{test_part_one_error, test_part_two_error};
 
     let file_input = include_str!("day20_input.txt");
-    test_part_one_error!(file_input => "Not implemented".to_string());
-    test_part_two_error!(file_input => "Not implemented".to_string());
+    test_part_one_error!(file_input => "Missing image enhancement algorithm");
+    test_part_two_error!(file_input => "Missing image enhancement algorithm");
 }
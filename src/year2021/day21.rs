@@ -1,16 +1,262 @@
-use crate::input::Input;
+use std::collections::HashMap;
 
-pub fn solve(_input: &mut Input) -> Result<u32, String> {
-    Err("Not implemented".to_string())
+use crate::common::parse_lines::lines;
+use crate::error::AocError;
+use crate::solution::Solution;
+
+pub struct Day;
+
+impl Solution for Day {
+    /// The starting position of each player
+    type Parsed = [u32; 2];
+    type Answer = u64;
+
+    fn parse(text: &str) -> Result<[u32; 2], AocError> {
+        parse(text)
+    }
+
+    fn part_one(&start: &[u32; 2]) -> Result<u64, AocError> {
+        Game::DETERMINISTIC.play_deterministic(start)
+    }
+
+    fn part_two(&start: &[u32; 2]) -> Result<u64, AocError> {
+        let wins = Game::DIRAC.count_dirac_wins(start)?;
+        Ok(wins[0].max(wins[1]))
+    }
+}
+
+/// The positions and scores of both players, and whose turn it is.
+type GameState = ([u32; 2], [u32; 2], usize);
+
+/// Frequencies of the sums of three rolls of the three-sided Dirac die,
+/// from 3 to 9.
+const DIRAC_ROLLS: [(u32, u64); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
+
+/// Game holds the rules that can change between variants of Dirac Dice.
+#[derive(Debug, Clone, Copy)]
+pub struct Game {
+    /// The number of spaces on the circular track, numbered from 1
+    pub board_size: u32,
+    /// The score a player has to reach to win
+    pub winning_score: u32,
+}
+
+impl Game {
+    /// The rules of the practice game played with the deterministic die.
+    pub const DETERMINISTIC: Self = Self {
+        board_size: 10,
+        winning_score: 1000,
+    };
+
+    /// The rules of the game played with the Dirac die.
+    pub const DIRAC: Self = Self {
+        board_size: 10,
+        winning_score: 21,
+    };
+
+    const DETERMINISTIC_DIE_SIDES: u32 = 100;
+
+    const MAX_DIRAC_SCORE: u32 = 100;
+
+    fn advance(&self, position: u32, steps: u32) -> u32 {
+        (position - 1 + steps) % self.board_size + 1
+    }
+
+    fn check_start(&self, start: [u32; 2]) -> Result<(), AocError> {
+        match start.iter().find(|&&p| !(1..=self.board_size).contains(&p)) {
+            Some(position) => Err(AocError::InvalidInput(format!(
+                "Starting position {} is not on a board of size {}",
+                position, self.board_size
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Plays with the deterministic die and returns the score of the losing
+    /// player multiplied by the number of times the die was rolled.
+    pub fn play_deterministic(&self, start: [u32; 2]) -> Result<u64, AocError> {
+        self.check_start(start)?;
+
+        let mut positions = start;
+        let mut scores = [0_u32; 2];
+        let mut rolls = 0_u32;
+        let mut turn = 0;
+
+        loop {
+            let steps = (0..3)
+                .map(|_| {
+                    rolls += 1;
+                    (rolls - 1) % Self::DETERMINISTIC_DIE_SIDES + 1
+                })
+                .sum::<u32>();
+            positions[turn] = self.advance(positions[turn], steps);
+            scores[turn] += positions[turn];
+            if scores[turn] >= self.winning_score {
+                return Ok(u64::from(scores[1 - turn]) * u64::from(rolls));
+            }
+            turn = 1 - turn;
+        }
+    }
+
+    /// Counts in how many universes each player wins with the Dirac die.
+    /// From a score of about 30 there are more than a u64 can count.
+    pub fn count_dirac_wins(&self, start: [u32; 2]) -> Result<[u64; 2], AocError> {
+        self.check_start(start)?;
+        // also bounds the depth of the recursion, one turn per point at worst
+        if self.winning_score > Self::MAX_DIRAC_SCORE {
+            return Err(AocError::Unsolvable(format!(
+                "Winning score {} is above the limit of {} for the Dirac die",
+                self.winning_score,
+                Self::MAX_DIRAC_SCORE
+            )));
+        }
+
+        let mut cache = HashMap::new();
+        self.dirac_wins(start, [0, 0], 0, &mut cache)
+            .ok_or_else(|| {
+                AocError::Unsolvable(format!(
+                    "Too many universes to count up to a score of {}",
+                    self.winning_score
+                ))
+            })
+    }
+
+    fn dirac_wins(
+        &self,
+        positions: [u32; 2],
+        scores: [u32; 2],
+        turn: usize,
+        cache: &mut HashMap<GameState, [u64; 2]>,
+    ) -> Option<[u64; 2]> {
+        if let Some(&wins) = cache.get(&(positions, scores, turn)) {
+            return Some(wins);
+        }
+
+        let mut wins = [0_u64, 0];
+        for (steps, frequency) in DIRAC_ROLLS {
+            let mut positions = positions;
+            let mut scores = scores;
+            positions[turn] = self.advance(positions[turn], steps);
+            scores[turn] += positions[turn];
+
+            if scores[turn] >= self.winning_score {
+                wins[turn] = wins[turn].checked_add(frequency)?;
+            } else {
+                let next = self.dirac_wins(positions, scores, 1 - turn, cache)?;
+                for player in 0..2 {
+                    wins[player] =
+                        wins[player].checked_add(frequency.checked_mul(next[player])?)?;
+                }
+            }
+        }
+
+        cache.insert((positions, scores, turn), wins);
+        Some(wins)
+    }
+}
+
+/// Parses the starting positions, which must be on the board of both games.
+fn parse(text: &str) -> Result<[u32; 2], AocError> {
+    let mut start = [0; 2];
+    let mut lines = lines(text);
+
+    for (player, position) in start.iter_mut().enumerate() {
+        let line = lines.next().ok_or_else(|| {
+            AocError::InvalidInput(format!(
+                "Missing starting position of player {}",
+                player + 1
+            ))
+        })?;
+        let prefix = format!("Player {} starting position:", player + 1);
+        let value = line
+            .strip_prefix(&prefix)
+            .ok_or_else(|| line.error(&format!("Expected {}", prefix)))?
+            .trim();
+        *position = value.parse()?;
+        if !(1..=Game::DIRAC.board_size).contains(position) {
+            return Err(value.error("Not a space of the board"));
+        }
+    }
+
+    Ok(start)
+}
+
+#[test]
+pub fn test_parse() {
+    assert_eq!(
+        parse("Player 1 starting position: 4\nPlayer 2 starting position: 11"),
+        Err(AocError::parse(2, 29, "11", "Not a space of the board"))
+    );
+    assert_eq!(
+        parse("Player 1 starting position: 4\nPlayer 1 starting position: 8"),
+        Err(AocError::parse(
+            2,
+            1,
+            "Player 1 starting position: 8",
+            "Expected Player 2 starting position:"
+        ))
+    );
+    assert_eq!(
+        parse("Player 1 starting position: x"),
+        Err(AocError::parse(1, 29, "x", "Not a valid u32"))
+    );
+}
+
+#[test]
+pub fn test_variants() -> Result<(), AocError> {
+    let game = Game {
+        board_size: 10,
+        winning_score: 1,
+    };
+    // the first player always wins on the first turn
+    assert_eq!(game.count_dirac_wins([4, 8])?, [27, 0]);
+    assert_eq!(game.play_deterministic([4, 8])?, 0);
+
+    let game = Game {
+        board_size: 5,
+        winning_score: 21,
+    };
+    assert_eq!(
+        game.play_deterministic([4, 8]),
+        Err(AocError::InvalidInput(
+            "Starting position 8 is not on a board of size 5".to_string()
+        ))
+    );
+
+    let game = Game {
+        board_size: 10,
+        winning_score: 40,
+    };
+    assert_eq!(
+        game.count_dirac_wins([4, 8]),
+        Err(AocError::Unsolvable(
+            "Too many universes to count up to a score of 40".to_string()
+        ))
+    );
+
+    let game = Game {
+        board_size: 10,
+        winning_score: 1_000_000,
+    };
+    assert_eq!(
+        game.count_dirac_wins([4, 8]),
+        Err(AocError::Unsolvable(
+            "Winning score 1000000 is above the limit of 100 for the Dirac die".to_string()
+        ))
+    );
+
+    Ok(())
 }
 
 #[test]
 pub fn test_example() -> Result<(), String> {
-    use crate::input::{test_part_one_error, test_part_two_error};
-    let example_input = "";
+    use crate::input::{test_part_one, test_part_two};
+    let example_input = "Player 1 starting position: 4
+    Player 2 starting position: 8";
+
+    test_part_one!(example_input => 739_785);
+    test_part_two!(example_input => 444_356_092_776_315);
 
-    test_part_one_error!(example_input => "Not implemented".to_string());
-    test_part_two_error!(example_input => "Not implemented".to_string());
     Ok(())
 }
 
@@ -19,6 +265,6 @@ pub fn tests() {
     use crate::input::{test_part_one_error, test_part_two_error};
 
     let file_input = include_str!("day21_input.txt");
-    test_part_one_error!(file_input => "Not implemented".to_string());
-    test_part_two_error!(file_input => "Not implemented".to_string());
+    test_part_one_error!(file_input => "Missing starting position of player 1");
+    test_part_two_error!(file_input => "Missing starting position of player 1");
 }
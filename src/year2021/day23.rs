@@ -1,16 +1,376 @@
-use crate::input::Input;
+use crate::common::parse_lines::{lines, Span};
+use crate::common::search::dijkstra;
+use crate::error::AocError;
+use crate::solution::Solution;
 
-pub fn solve(_input: &mut Input) -> Result<u32, String> {
-    Err("Not implemented".to_string())
+/// The two rows unfolded from the diagram in part two, indented as they
+/// appear below the hallway's left wall.
+const FOLDED_ROWS: [&str; 2] = ["  #D#C#B#A#", "  #D#B#A#C#"];
+
+/// Diagram holds the burrow as given in the input with its starting state,
+/// and the input itself to unfold in part two.
+pub struct Diagram {
+    text: String,
+    folded: (Burrow, State),
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Diagram;
+    type Answer = u32;
+
+    fn parse(text: &str) -> Result<Diagram, AocError> {
+        let folded = Burrow::parse(&lines(text).collect::<Vec<_>>())?;
+        Ok(Diagram {
+            text: text.to_string(),
+            folded,
+        })
+    }
+
+    fn part_one(diagram: &Diagram) -> Result<u32, AocError> {
+        let (burrow, state) = &diagram.folded;
+        burrow.least_energy(state.clone())
+    }
+
+    fn part_two(diagram: &Diagram) -> Result<u32, AocError> {
+        let (burrow, state) = Burrow::parse(&unfold(&diagram.text)?)?;
+        burrow.least_energy(state)
+    }
+}
+
+/// Inserts the folded rows right below the first row of the rooms. They share
+/// its line number in errors.
+fn unfold(text: &str) -> Result<Vec<Span<'_>>, AocError> {
+    let mut lines = lines(text).collect::<Vec<_>>();
+    if lines.len() < 3 {
+        return Err(AocError::InvalidInput(
+            "Burrow diagram is too short to unfold".to_string(),
+        ));
+    }
+    let line = lines[2].line;
+    let column = lines[1].column;
+    let folded_rows = FOLDED_ROWS.map(|row| {
+        Span {
+            line,
+            column,
+            text: row,
+        }
+        .trim()
+    });
+    lines.splice(3..3, folded_rows);
+    Ok(lines)
+}
+
+/// State lists the content of the hallway, followed by the content of each
+/// room from top to bottom. 0 is an empty space, otherwise the amphipod type
+/// plus 1.
+type State = Vec<u8>;
+
+/// Burrow describes the shape of the burrow, which never changes during the
+/// search.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Burrow {
+    /// The number of spaces in the hallway
+    hallway: usize,
+    /// The hallway position right outside each room
+    doors: Vec<usize>,
+    /// The number of amphipods each room holds
+    depth: usize,
+}
+
+impl Burrow {
+    fn parse(lines: &[Span]) -> Result<(Self, State), AocError> {
+        let hallway_line = lines
+            .get(1)
+            .ok_or_else(|| AocError::InvalidInput("Missing burrow hallway".to_string()))?;
+        if !hallway_line.text.starts_with('#') {
+            return Err(hallway_line.error("Expected the hallway's left wall"));
+        }
+        let wall = hallway_line.column;
+        let mut state = Self::parse_cells(hallway_line, wall)?
+            .into_iter()
+            .map(|(_, cell)| cell)
+            .collect::<State>();
+        let hallway = state.len();
+
+        let mut doors = Vec::new();
+        let mut rooms: Vec<Vec<u8>> = Vec::new();
+        for line in &lines[2..] {
+            let cells = Self::parse_cells(line, wall)?;
+            if cells.is_empty() {
+                continue;
+            }
+
+            if doors.is_empty() {
+                doors = cells.iter().map(|&(x, _)| x).collect();
+                rooms = vec![Vec::new(); doors.len()];
+            }
+            if cells.iter().map(|&(x, _)| x).ne(doors.iter().copied()) {
+                return Err(line.error("Rooms are not aligned"));
+            }
+            for (room, (_, cell)) in rooms.iter_mut().zip(cells) {
+                room.push(cell);
+            }
+        }
+
+        let depth = rooms.first().map(Vec::len).unwrap_or_default();
+        if depth == 0 {
+            return Err(AocError::InvalidInput("Missing burrow rooms".to_string()));
+        }
+        if doors.iter().any(|&door| door >= hallway) {
+            return Err(AocError::InvalidInput(
+                "Rooms must open on the hallway".to_string(),
+            ));
+        }
+        let kinds = doors.len() as u8;
+        if let Some(&cell) = state
+            .iter()
+            .chain(rooms.iter().flatten())
+            .find(|&&c| c > kinds)
+        {
+            return Err(AocError::InvalidInput(format!(
+                "Amphipod {} has no room",
+                (b'A' + cell - 1) as char
+            )));
+        }
+        for kind in 1..=kinds {
+            let count = rooms.iter().flatten().filter(|&&c| c == kind).count()
+                + state.iter().filter(|&&c| c == kind).count();
+            if count != depth {
+                return Err(AocError::InvalidInput(format!(
+                    "Expected {} amphipods of type {}, found {}",
+                    depth,
+                    (b'A' + kind - 1) as char,
+                    count
+                )));
+            }
+        }
+
+        state.extend(rooms.into_iter().flatten());
+        Ok((
+            Self {
+                hallway,
+                doors,
+                depth,
+            },
+            state,
+        ))
+    }
+
+    /// Parses the open cells of a line of the diagram along with their
+    /// position, the hallway starting at 0 right after the left wall in
+    /// column `wall`.
+    fn parse_cells(line: &Span, wall: usize) -> Result<Vec<(usize, u8)>, AocError> {
+        line.text
+            .chars()
+            .enumerate()
+            .filter(|&(_, c)| c != '#' && c != ' ')
+            .map(|(i, c)| {
+                let column = line.column + i;
+                let error = |message| AocError::parse(line.line, column, &c.to_string(), message);
+                let cell = match c {
+                    '.' => 0,
+                    'A'..='D' => c as u8 - b'A' + 1,
+                    _ => return Err(error("Invalid burrow cell")),
+                };
+                let position = column
+                    .checked_sub(wall + 1)
+                    .ok_or_else(|| error("Cell left of the hallway"))?;
+                Ok((position, cell))
+            })
+            .collect()
+    }
+
+    fn room_cell(&self, room: usize, slot: usize) -> usize {
+        self.hallway + room * self.depth + slot
+    }
+
+    fn energy(amphipod: u8) -> u32 {
+        10_u32.pow(u32::from(amphipod - 1))
+    }
+
+    /// Tells whether the hallway between `from` and `to` is free, `from` excluded.
+    fn is_path_free(state: &[u8], from: usize, to: usize) -> bool {
+        let path = if from < to {
+            &state[from + 1..=to]
+        } else {
+            &state[to..from]
+        };
+        path.iter().all(|&c| c == 0)
+    }
+
+    /// Tells whether the room only contains amphipods that belong there,
+    /// from `slot` to the bottom.
+    fn is_settled(&self, state: &[u8], room: usize, slot: usize) -> bool {
+        (slot..self.depth).all(|s| {
+            let cell = state[self.room_cell(room, s)];
+            cell == 0 || usize::from(cell) == room + 1
+        })
+    }
+
+    fn is_done(&self, state: &[u8]) -> bool {
+        (0..self.doors.len()).all(|room| {
+            (0..self.depth).all(|slot| usize::from(state[self.room_cell(room, slot)]) == room + 1)
+        })
+    }
+
+    fn moves(&self, state: &[u8]) -> Vec<(State, u32)> {
+        let mut moves = Vec::new();
+
+        // from the hallway straight into the destination room
+        for position in 0..self.hallway {
+            let amphipod = state[position];
+            if amphipod == 0 {
+                continue;
+            }
+            let room = usize::from(amphipod - 1);
+            let door = self.doors[room];
+            if !self.is_settled(state, room, 0) || !Self::is_path_free(state, position, door) {
+                continue;
+            }
+            if let Some(slot) = (0..self.depth)
+                .rev()
+                .find(|&s| state[self.room_cell(room, s)] == 0)
+            {
+                let mut next = state.to_vec();
+                next.swap(position, self.room_cell(room, slot));
+                let steps = position.abs_diff(door) + slot + 1;
+                moves.push((next, Self::energy(amphipod) * steps as u32));
+            }
+        }
+
+        // from the top of a room out to a hallway space that is not a door
+        for (room, &door) in self.doors.iter().enumerate() {
+            let slot = match (0..self.depth).find(|&s| state[self.room_cell(room, s)] != 0) {
+                Some(slot) => slot,
+                None => continue,
+            };
+            if self.is_settled(state, room, slot) {
+                continue;
+            }
+            let cell = self.room_cell(room, slot);
+            for position in 0..self.hallway {
+                if self.doors.contains(&position) || !Self::is_path_free(state, door, position) {
+                    continue;
+                }
+                let mut next = state.to_vec();
+                next.swap(cell, position);
+                let steps = position.abs_diff(door) + slot + 1;
+                moves.push((next, Self::energy(state[cell]) * steps as u32));
+            }
+        }
+
+        moves
+    }
+
+    fn least_energy(&self, start: State) -> Result<u32, AocError> {
+        dijkstra(
+            start,
+            |state| self.moves(state),
+            |state| self.is_done(state),
+        )
+        .map(|path| path.cost)
+        .ok_or_else(|| AocError::Unsolvable("Amphipods cannot be organized".to_string()))
+    }
+}
+
+#[test]
+pub fn test_parse() -> Result<(), AocError> {
+    let parse = |diagram: &[&str]| Burrow::parse(&lines(&diagram.join("\n")).collect::<Vec<_>>());
+    let diagram = [
+        "#############",
+        "#.A.........#",
+        "###.#B#",
+        "  #A#B#",
+        "  #####",
+    ];
+    let (burrow, state) = parse(&diagram)?;
+
+    assert_eq!(
+        burrow,
+        Burrow {
+            hallway: 11,
+            doors: vec![2, 4],
+            depth: 2
+        }
+    );
+    assert_eq!(state.len(), 15);
+    assert_eq!(burrow.least_energy(state), Ok(2));
+
+    let unbalanced = [
+        "#############",
+        "#...........#",
+        "###B#B#",
+        "  #A#B#",
+        "  #####",
+    ];
+    assert_eq!(
+        parse(&unbalanced),
+        Err(AocError::InvalidInput(
+            "Expected 2 amphipods of type A, found 1".to_string()
+        ))
+    );
+
+    let misaligned = ["#############", "#...........#", "###B#A#", "   #A#B#"];
+    assert_eq!(
+        parse(&misaligned),
+        Err(AocError::parse(4, 4, "#A#B#", "Rooms are not aligned"))
+    );
+    let invalid = ["#############", "#...........#", "###B#A#", "  #A#E#"];
+    assert_eq!(
+        parse(&invalid),
+        Err(AocError::parse(4, 6, "E", "Invalid burrow cell"))
+    );
+
+    let no_wall = ["#############", "...........#", "###B#A#", "  #A#B#"];
+    assert_eq!(
+        parse(&no_wall),
+        Err(AocError::parse(
+            2,
+            1,
+            "...........#",
+            "Expected the hallway's left wall"
+        ))
+    );
+    let outside = ["#############", "  #...........#", ".##B#A#", "  #A#B#"];
+    assert_eq!(
+        parse(&outside),
+        Err(AocError::parse(3, 1, ".", "Cell left of the hallway"))
+    );
+
+    Ok(())
 }
 
 #[test]
 pub fn test_example() -> Result<(), String> {
-    use crate::input::{test_part_one_error, test_part_two_error};
-    let example_input = "";
+    use crate::input::{test_part_one, test_part_two, test_part_two_error};
+    let example_input = "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########";
+
+    test_part_one!(example_input => 12521);
+    test_part_two!(example_input => 44169);
+
+    let indented_input = "    #############
+    #...........#
+    ###B#C#B#D###
+      #A#D#C#A#
+      #########";
+    test_part_one!(indented_input => 12521);
+    test_part_two!(indented_input => 44169);
+
+    // a room layout the folded rows do not fit only fails part two
+    let small_input = "#############
+#.A.........#
+###.#B#
+  #A#B#
+  #####";
+    test_part_one!(small_input => 2);
+    test_part_two_error!(small_input => "Line 3, column 3: Rooms are not aligned #D#C#B#A#");
 
-    test_part_one_error!(example_input => "Not implemented".to_string());
-    test_part_two_error!(example_input => "Not implemented".to_string());
     Ok(())
 }
 
@@ -19,6 +379,6 @@ pub fn tests() {
     use crate::input::{test_part_one_error, test_part_two_error};
 
     let file_input = include_str!("day23_input.txt");
-    test_part_one_error!(file_input => "Not implemented".to_string());
-    test_part_two_error!(file_input => "Not implemented".to_string());
+    test_part_one_error!(file_input => "Missing burrow hallway");
+    test_part_two_error!(file_input => "Missing burrow hallway");
 }
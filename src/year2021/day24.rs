@@ -1,16 +1,468 @@
-use crate::input::Input;
+use crate::common::parse_lines::{lines, Span};
+use crate::error::AocError;
+use crate::solution::Solution;
+use std::fmt;
 
-pub fn solve(_input: &mut Input) -> Result<u32, String> {
-    Err("Not implemented".to_string())
+/// Monad is the MONAD program, along with the parameters of its digit blocks.
+pub struct Monad {
+    program: Vec<Instruction>,
+    blocks: Vec<Block>,
+}
+
+impl Monad {
+    /// Finds the largest or smallest model number accepted by MONAD.
+    fn model_number(&self, largest: bool) -> Result<u64, AocError> {
+        let model_number = find_model_number(&self.blocks, largest)?;
+
+        // double check the analysis against the actual program
+        if check_model_number(&self.program, model_number)? {
+            Ok(model_number)
+        } else {
+            Err(AocError::Unsolvable(format!(
+                "Model number {} is rejected by MONAD",
+                model_number
+            )))
+        }
+    }
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Monad;
+    type Answer = u64;
+
+    fn parse(text: &str) -> Result<Monad, AocError> {
+        let program = parse(text)?;
+        let blocks = Block::extract_all(&program)?;
+        Ok(Monad { program, blocks })
+    }
+
+    fn part_one(monad: &Monad) -> Result<u64, AocError> {
+        monad.model_number(true)
+    }
+
+    fn part_two(monad: &Monad) -> Result<u64, AocError> {
+        monad.model_number(false)
+    }
+}
+
+const MODEL_NUMBER_DIGITS: usize = 14;
+
+/// The instructions of each digit block of MONAD. Only the `div z`, `add x`
+/// and `add y` arguments at index 4, 5 and 15 change between blocks.
+const BLOCK_TEMPLATE: &str = "inp w
+mul x 0
+add x z
+mod x 26
+div z 1
+add x 0
+eql x w
+eql x 0
+mul y 0
+add y 25
+mul y x
+add y 1
+mul z y
+mul y 0
+add y w
+add y 0
+mul y x
+add z y";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(usize),
+    Number(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Inp(usize),
+    Add(usize, Operand),
+    Mul(usize, Operand),
+    Div(usize, Operand),
+    Mod(usize, Operand),
+    Eql(usize, Operand),
+}
+
+impl Instruction {
+    fn parse(line: Span) -> Result<Self, AocError> {
+        let register = |name: &Span| match name.text {
+            "w" => Ok(0),
+            "x" => Ok(1),
+            "y" => Ok(2),
+            "z" => Ok(3),
+            _ => Err(name.error("Invalid register")),
+        };
+        let operand = |arg: &Span| match arg.text.parse::<i64>() {
+            Ok(n) => Ok(Operand::Number(n)),
+            Err(_) => register(arg).map(Operand::Register),
+        };
+
+        let words = line.words().collect::<Vec<_>>();
+        match words.split_first().map(|(name, args)| (name.text, args)) {
+            Some(("inp", [a])) => Ok(Self::Inp(register(a)?)),
+            Some(("add", [a, b])) => Ok(Self::Add(register(a)?, operand(b)?)),
+            Some(("mul", [a, b])) => Ok(Self::Mul(register(a)?, operand(b)?)),
+            Some(("div", [a, b])) => Ok(Self::Div(register(a)?, operand(b)?)),
+            Some(("mod", [a, b])) => Ok(Self::Mod(register(a)?, operand(b)?)),
+            Some(("eql", [a, b])) => Ok(Self::Eql(register(a)?, operand(b)?)),
+            _ => Err(line.error("Invalid instruction")),
+        }
+    }
+}
+
+const REGISTER_NAMES: [char; 4] = ['w', 'x', 'y', 'z'];
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Register(r) => write!(f, "{}", REGISTER_NAMES[*r]),
+            Self::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+/// Writes the instruction back the way it is written in the program.
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, a, b) = match *self {
+            Self::Inp(a) => return write!(f, "inp {}", REGISTER_NAMES[a]),
+            Self::Add(a, b) => ("add", a, b),
+            Self::Mul(a, b) => ("mul", a, b),
+            Self::Div(a, b) => ("div", a, b),
+            Self::Mod(a, b) => ("mod", a, b),
+            Self::Eql(a, b) => ("eql", a, b),
+        };
+        write!(f, "{} {} {}", name, REGISTER_NAMES[a], b)
+    }
+}
+
+/// Alu is the arithmetic logic unit of the submarine, with its four
+/// registers `w`, `x`, `y` and `z`.
+#[derive(Debug, Default)]
+pub struct Alu {
+    pub registers: [i64; 4],
+}
+
+impl Alu {
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(r) => self.registers[r],
+            Operand::Number(n) => n,
+        }
+    }
+
+    /// Runs the program, reading `inp` values from `input` in order.
+    pub fn run(&mut self, program: &[Instruction], input: &[i64]) -> Result<(), AocError> {
+        let mut input = input.iter();
+
+        for (i, &instruction) in program.iter().enumerate() {
+            let error = |message: &str| {
+                AocError::InvalidInput(format!(
+                    "Instruction {} ({}): {}",
+                    i + 1,
+                    instruction,
+                    message
+                ))
+            };
+            let overflow = || error("overflow");
+            match instruction {
+                Instruction::Inp(a) => {
+                    self.registers[a] = *input.next().ok_or_else(|| error("input exhausted"))?;
+                }
+                Instruction::Add(a, b) => {
+                    self.registers[a] = self.registers[a]
+                        .checked_add(self.value(b))
+                        .ok_or_else(overflow)?;
+                }
+                Instruction::Mul(a, b) => {
+                    self.registers[a] = self.registers[a]
+                        .checked_mul(self.value(b))
+                        .ok_or_else(overflow)?;
+                }
+                Instruction::Div(a, b) => {
+                    let b = self.value(b);
+                    if b == 0 {
+                        return Err(error("division by zero"));
+                    }
+                    // i64::MIN / -1 does not fit
+                    self.registers[a] = self.registers[a].checked_div(b).ok_or_else(overflow)?;
+                }
+                Instruction::Mod(a, b) => {
+                    let b = self.value(b);
+                    if self.registers[a] < 0 || b <= 0 {
+                        return Err(error("negative modulus"));
+                    }
+                    self.registers[a] = self.registers[a].checked_rem(b).ok_or_else(overflow)?;
+                }
+                Instruction::Eql(a, b) => {
+                    self.registers[a] = i64::from(self.registers[a] == self.value(b));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs MONAD on the digits of `number`, telling whether it is a valid model
+/// number. Numbers containing a 0 digit are never valid.
+pub fn check_model_number(program: &[Instruction], number: u64) -> Result<bool, AocError> {
+    let digits = number
+        .to_string()
+        .bytes()
+        .map(|d| i64::from(d - b'0'))
+        .collect::<Vec<_>>();
+
+    if digits.len() != MODEL_NUMBER_DIGITS {
+        return Err(AocError::InvalidInput(format!(
+            "Model number {} does not have {} digits",
+            number, MODEL_NUMBER_DIGITS
+        )));
+    }
+    if digits.contains(&0) {
+        return Ok(false);
+    }
+
+    let mut alu = Alu::default();
+    alu.run(program, &digits)?;
+    Ok(alu.registers[3] == 0)
+}
+
+/// Block holds the parameters of the MONAD block checking a single digit.
+///
+/// Register z works as a base 26 stack. A block dividing z by 1 pushes
+/// `digit + offset`, while a block dividing by 26 pops the top value and can
+/// only avoid pushing again when `popped + check == digit`.
+#[derive(Debug, Clone, Copy)]
+pub struct Block {
+    pops: bool,
+    check: i64,
+    offset: i64,
+}
+
+impl Block {
+    fn extract_all(program: &[Instruction]) -> Result<Vec<Self>, AocError> {
+        let template = parse(BLOCK_TEMPLATE)?;
+
+        if program.len() != template.len() * MODEL_NUMBER_DIGITS {
+            return Err(AocError::InvalidInput(format!(
+                "MONAD must have {} instructions, found {}",
+                template.len() * MODEL_NUMBER_DIGITS,
+                program.len()
+            )));
+        }
+
+        program
+            .chunks(template.len())
+            .enumerate()
+            .map(|(i, block)| {
+                let matches_template = block
+                    .iter()
+                    .zip(template.iter())
+                    .enumerate()
+                    .all(|(j, (a, b))| [4, 5, 15].contains(&j) || a == b);
+
+                match (matches_template, block[4], block[5], block[15]) {
+                    (
+                        true,
+                        Instruction::Div(3, Operand::Number(divisor @ (1 | 26))),
+                        Instruction::Add(1, Operand::Number(check)),
+                        Instruction::Add(2, Operand::Number(offset)),
+                    ) => Ok(Self {
+                        pops: divisor == 26,
+                        check,
+                        offset,
+                    }),
+                    _ => Err(AocError::InvalidInput(format!(
+                        "Block {} does not match the MONAD pattern",
+                        i + 1
+                    ))),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Pairs each pushing block with the block popping its value, and picks the
+/// largest or smallest digits satisfying `digit[pop] = digit[push] + diff`.
+fn find_model_number(blocks: &[Block], largest: bool) -> Result<u64, AocError> {
+    let mut digits = [0_i64; MODEL_NUMBER_DIGITS];
+    let mut stack = Vec::new();
+
+    for (i, block) in blocks.iter().enumerate() {
+        if !block.pops {
+            stack.push(i);
+            continue;
+        }
+
+        let j = stack.pop().ok_or_else(|| {
+            AocError::Unsolvable(format!("Block {} pops from an empty stack", i + 1))
+        })?;
+        let diff = blocks[j].offset + block.check;
+        if diff.abs() > 8 {
+            return Err(AocError::Unsolvable(format!(
+                "Digits {} and {} cannot differ by {}",
+                j + 1,
+                i + 1,
+                diff
+            )));
+        }
+
+        let pushed = if largest {
+            9.min(9 - diff)
+        } else {
+            1.max(1 - diff)
+        };
+        digits[j] = pushed;
+        digits[i] = pushed + diff;
+    }
+
+    if !stack.is_empty() {
+        return Err(AocError::Unsolvable(
+            "MONAD never accepts: z is left non-zero".to_string(),
+        ));
+    }
+
+    Ok(digits.iter().fold(0, |acc, &d| acc * 10 + d as u64))
+}
+
+fn parse(text: &str) -> Result<Vec<Instruction>, AocError> {
+    lines(text).map(Instruction::parse).collect()
 }
 
 #[test]
-pub fn test_example() -> Result<(), String> {
-    use crate::input::{test_part_one_error, test_part_two_error};
-    let example_input = "";
+pub fn test_alu() -> Result<(), AocError> {
+    let negate = parse("inp x\nmul x -1")?;
+    let mut alu = Alu::default();
+    alu.run(&negate, &[7])?;
+    assert_eq!(alu.registers[1], -7);
+
+    let three_times = parse("inp z\ninp x\nmul z 3\neql z x")?;
+    let mut alu = Alu::default();
+    alu.run(&three_times, &[3, 9])?;
+    assert_eq!(alu.registers[3], 1);
+    let mut alu = Alu::default();
+    alu.run(&three_times, &[3, 8])?;
+    assert_eq!(alu.registers[3], 0);
+
+    let binary = parse(
+        "inp w
+        add z w
+        mod z 2
+        div w 2
+        add y w
+        mod y 2
+        div w 2
+        add x w
+        mod x 2
+        div w 2
+        mod w 2",
+    )?;
+    let mut alu = Alu::default();
+    alu.run(&binary, &[13])?;
+    assert_eq!(alu.registers, [1, 1, 0, 1]);
+
+    let failures = [
+        (
+            "inp x\ndiv x y",
+            1,
+            "Instruction 2 (div x y): division by zero",
+        ),
+        (
+            "inp x\nmod x 2",
+            -1,
+            "Instruction 2 (mod x 2): negative modulus",
+        ),
+        ("inp x\ninp y", 1, "Instruction 2 (inp y): input exhausted"),
+        (
+            "inp x\nadd x 1",
+            i64::MAX,
+            "Instruction 2 (add x 1): overflow",
+        ),
+        (
+            "inp x\nmul x x",
+            1 << 32,
+            "Instruction 2 (mul x x): overflow",
+        ),
+        (
+            "inp x\ndiv x -1",
+            i64::MIN,
+            "Instruction 2 (div x -1): overflow",
+        ),
+    ];
+    for (program, input, message) in failures {
+        assert_eq!(
+            Alu::default().run(&parse(program)?, &[input]),
+            Err(AocError::InvalidInput(message.to_string()))
+        );
+    }
+    assert_eq!(
+        parse("inp x\nsub x 1"),
+        Err(AocError::parse(2, 1, "sub x 1", "Invalid instruction"))
+    );
+    assert_eq!(
+        parse("inp x\n  add x v"),
+        Err(AocError::parse(2, 9, "v", "Invalid register"))
+    );
+    assert_eq!(
+        parse(","),
+        Err(AocError::parse(1, 1, ",", "Invalid instruction"))
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+fn monad(blocks: &[(i64, i64, i64)]) -> String {
+    blocks
+        .iter()
+        .map(|&(divisor, check, offset)| {
+            BLOCK_TEMPLATE
+                .replace("div z 1", &format!("div z {}", divisor))
+                .replace("add x 0", &format!("add x {}", check))
+                .replace("add y 0", &format!("add y {}", offset))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+pub fn test_example() -> Result<(), AocError> {
+    use crate::input::{test_part_one, test_part_one_error, test_part_two};
+
+    let text = monad(&[
+        (1, 12, 5),
+        (1, 11, 10),
+        (26, -12, 8),
+        (1, 13, 2),
+        (26, -2, 1),
+        (26, -4, 6),
+        (1, 10, 7),
+        (1, 14, 1),
+        (26, 0, 9),
+        (1, 15, 3),
+        (26, -9, 2),
+        (26, -8, 12),
+        (1, 11, 4),
+        (26, -10, 3),
+    ]);
+    let example_input = text.as_str();
+
+    test_part_one!(example_input => 89_799_998_993_893);
+    test_part_two!(example_input => 13_111_221_271_171);
+
+    let program = parse(example_input)?;
+    assert!(check_model_number(&program, 89_799_998_993_893)?);
+    assert!(!check_model_number(&program, 89_799_998_993_894)?);
+    assert!(!check_model_number(&program, 89_799_998_993_803)?);
+
+    let broken = text.replacen("mul y 0", "mul y 1", 1);
+    let broken_input = broken.as_str();
+    test_part_one_error!(broken_input => "Block 1 does not match the MONAD pattern");
 
-    test_part_one_error!(example_input => "Not implemented".to_string());
-    test_part_two_error!(example_input => "Not implemented".to_string());
     Ok(())
 }
 
@@ -19,6 +471,6 @@ pub fn tests() {
     use crate::input::{test_part_one_error, test_part_two_error};
 
     let file_input = include_str!("day24_input.txt");
-    test_part_one_error!(file_input => "Not implemented".to_string());
-    test_part_two_error!(file_input => "Not implemented".to_string());
+    test_part_one_error!(file_input => "MONAD must have 252 instructions, found 0");
+    test_part_two_error!(file_input => "MONAD must have 252 instructions, found 0");
 }
@@ -1,16 +1,210 @@
-use crate::input::Input;
+use std::fmt;
+use std::str::FromStr;
 
-pub fn solve(_input: &mut Input) -> Result<u32, String> {
-    Err("Not implemented".to_string())
+use crate::common::grid::Grid;
+use crate::error::AocError;
+use crate::solution::Solution;
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = SeaFloor;
+    type Answer = u32;
+
+    fn parse(text: &str) -> Result<SeaFloor, AocError> {
+        text.parse::<SeaFloor>()
+    }
+
+    fn part_one(sea_floor: &SeaFloor) -> Result<u32, AocError> {
+        let moving_steps = sea_floor.clone().settle()?;
+
+        // the first step where nothing moves comes right after the moving ones
+        Ok(moving_steps as u32 + 1)
+    }
+
+    fn part_two(_: &SeaFloor) -> Result<u32, AocError> {
+        Err(AocError::NotImplemented)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    East,
+    South,
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let c = match self {
+            Self::Empty => '.',
+            Self::East => '>',
+            Self::South => 'v',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+/// SeaFloor is the grid of sea cucumbers. Herds leaving an edge wrap around
+/// to the opposite one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeaFloor {
+    cells: Grid<Cell>,
+}
+
+impl FromStr for SeaFloor {
+    type Err = AocError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let cells = Grid::parse_with(text, |c| match c {
+            '.' => Some(Cell::Empty),
+            '>' => Some(Cell::East),
+            'v' => Some(Cell::South),
+            _ => None,
+        })?;
+
+        if cells.is_empty() {
+            return Err(AocError::InvalidInput("Empty sea floor".to_string()));
+        }
+
+        Ok(Self { cells })
+    }
+}
+
+impl SeaFloor {
+    /// Returns the sea floor once the herds moved `steps` times, such as in
+    /// the worked examples of the puzzle.
+    pub fn after_steps(&self, steps: usize) -> Self {
+        let mut sea_floor = self.clone();
+        for _ in 0..steps {
+            if !sea_floor.step() {
+                break;
+            }
+        }
+        sea_floor
+    }
+
+    /// Moves the herds until they no longer move, returning the number of
+    /// steps in which a sea cucumber moved. Herds going back to an earlier
+    /// state would repeat it forever, so they are unsolvable.
+    pub fn settle(&mut self) -> Result<usize, AocError> {
+        // Brent's cycle detection: a cycle brings the herds back to the state
+        // saved at the last power of two once it is longer than the cycle
+        let mut saved = self.cells.clone();
+        let mut power = 1;
+        let mut moving_steps = 0;
+        while self.step() {
+            moving_steps += 1;
+            if self.cells == saved {
+                return Err(AocError::Unsolvable(
+                    "The sea cucumbers never stop moving".to_string(),
+                ));
+            }
+            if moving_steps == power {
+                saved = self.cells.clone();
+                power *= 2;
+            }
+        }
+        Ok(moving_steps)
+    }
+
+    /// Moves the east-facing herd and then the south-facing one, returning
+    /// whether any sea cucumber moved.
+    fn step(&mut self) -> bool {
+        let moved_east = self.move_herd(Cell::East, 1, 0);
+        let moved_south = self.move_herd(Cell::South, 0, 1);
+        moved_east || moved_south
+    }
+
+    /// Moves every sea cucumber of the herd whose destination was empty
+    /// before any of them moved.
+    fn move_herd(&mut self, herd: Cell, dx: usize, dy: usize) -> bool {
+        let (width, height) = (self.cells.width(), self.cells.height());
+        let moves = self
+            .cells
+            .iter()
+            .filter(|&(_, &cell)| cell == herd)
+            .map(|((x, y), _)| ((x, y), ((x + dx) % width, (y + dy) % height)))
+            .filter(|&(_, to)| self.cells[to] == Cell::Empty)
+            .collect::<Vec<_>>();
+
+        for &(from, to) in moves.iter() {
+            self.cells[to] = herd;
+            self.cells[from] = Cell::Empty;
+        }
+
+        !moves.is_empty()
+    }
+}
+
+impl fmt::Display for SeaFloor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.cells)
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE_INPUT: &str = "v...>>.vv>
+    .vv>>.vv..
+    >>.>v>...v
+    >>v>>.>.v.
+    v>v.vv.v..
+    >.>>..v...
+    .vv..>.>v.
+    v.v..>>v.v
+    ....v..v.>";
+
+#[cfg(test)]
+fn state_after(text: &str, steps: usize) -> Result<SeaFloor, AocError> {
+    Ok(text.parse::<SeaFloor>()?.after_steps(steps))
 }
 
 #[test]
-pub fn test_example() -> Result<(), String> {
-    use crate::input::{test_part_one_error, test_part_two_error};
-    let example_input = "";
+pub fn test_steps() -> Result<(), AocError> {
+    assert_eq!(state_after("...>>>>>...", 1)?.to_string(), "...>>>>.>..");
+    assert_eq!(state_after("...>>>>>...", 2)?.to_string(), "...>>>.>.>.");
+
+    let wrapping = "..........
+    .>v....v..
+    .......>..
+    ..........";
+    assert_eq!(
+        state_after(wrapping, 1)?.to_string(),
+        "..........\n.>........\n..v....v>.\n.........."
+    );
+
+    assert_eq!(
+        state_after(EXAMPLE_INPUT, 1)?.to_string(),
+        "....>.>v.>
+v.v>.>v.v.
+>v>>..>v..
+>>v>v>.>.v
+.>v.v...v.
+v>>.>vvv..
+..v...>>..
+vv...>>vv.
+>.v.v..v.v"
+    );
+    assert_eq!(
+        state_after(EXAMPLE_INPUT, 58)?,
+        state_after(EXAMPLE_INPUT, 57)?
+    );
+
+    Ok(())
+}
+
+#[test]
+pub fn test_example() -> Result<(), AocError> {
+    use crate::input::{test_part_one, test_part_one_error, test_part_two_error};
+
+    test_part_one!(EXAMPLE_INPUT => 58);
+    test_part_two_error!(EXAMPLE_INPUT => AocError::NotImplemented);
+    test_part_one_error!("..>\n.x." => "Line 2, column 2: Invalid character x");
+    test_part_one_error!(">." => "The sea cucumbers never stop moving");
+    test_part_one_error!(">v\n.." => "The sea cucumbers never stop moving");
+    // a cycle of 7 steps that never comes back to the starting state
+    test_part_one_error!("...>>.." => "The sea cucumbers never stop moving");
 
-    test_part_one_error!(example_input => "Not implemented".to_string());
-    test_part_two_error!(example_input => "Not implemented".to_string());
     Ok(())
 }
 
@@ -19,6 +213,6 @@ pub fn tests() {
     use crate::input::{test_part_one_error, test_part_two_error};
 
     let file_input = include_str!("day25_input.txt");
-    test_part_one_error!(file_input => "Not implemented".to_string());
-    test_part_two_error!(file_input => "Not implemented".to_string());
+    test_part_one_error!(file_input => "Empty sea floor");
+    test_part_two_error!(file_input => "Empty sea floor");
 }